@@ -0,0 +1,139 @@
+//! Keeps the completed tasks of past days, so they can be searched and restored later.
+
+use super::persistence;
+use super::pomodoro::Task;
+
+use iced::time::Duration;
+use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text, text_input};
+use iced::{Center, Element, Length};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Name of the JSON file holding the archived tasks.
+const ARCHIVE_FILE: &str = "archive.json";
+
+/// A completed task together with the day it was completed on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    completed: NaiveDate,
+    task: Task,
+}
+
+/// Holds the archived tasks and the search text of the Archive tab.
+#[derive(Debug)]
+pub struct Archive {
+    entries: Vec<Entry>,
+    search: String,
+}
+
+/// Messages used for updating the archive tab.
+#[derive(Debug, Clone)]
+pub enum Message {
+    Search(String),
+    /// Moves the archived task at the index back to the task list.
+    Restore(usize),
+}
+
+impl Archive {
+    /// Loads the archived tasks from persistent storage.
+    pub fn new() -> Self {
+        Self {
+            entries: persistence::load(ARCHIVE_FILE).unwrap_or_default(),
+            search: String::new(),
+        }
+    }
+
+    /// Archives the completed tasks under the day each was completed on. Tasks completed
+    /// before that day was recorded fall back to `ended`, the day that ends.
+    pub fn add(&mut self, tasks: Vec<Task>, ended: NaiveDate) {
+        if tasks.is_empty() {
+            return;
+        }
+        self.entries.extend(tasks.into_iter().map(|task| Entry {
+            completed: task.completed_on().unwrap_or(ended),
+            task,
+        }));
+        self.save();
+    }
+
+    /// Processes messages and returns the task to restore, if one was picked.
+    pub fn update(&mut self, message: Message) -> Option<Task> {
+        match message {
+            Message::Search(search) => {
+                self.search = search;
+                None
+            }
+            Message::Restore(index) => {
+                if index >= self.entries.len() {
+                    return None;
+                }
+                let entry = self.entries.remove(index);
+                self.save();
+                Some(entry.task)
+            }
+        }
+    }
+
+    /// Saves the archived tasks to disk.
+    fn save(&self) {
+        persistence::save(ARCHIVE_FILE, &self.entries).ok();
+    }
+
+    /// Builds the search field and the archived tasks matching it, most recent first.
+    pub fn view(&self) -> Element<'_, Message> {
+        let search = self.search.trim();
+        let rows: Vec<Element<_>> = self
+            .entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| {
+                entry.task.matches_search(search) || entry.completed.to_string().contains(search)
+            })
+            .map(|(index, entry)| {
+                let labels = entry.task.format_labels();
+                row![
+                    text(entry.completed.format("%Y-%m-%d").to_string()),
+                    column![text(entry.task.desc())]
+                        .push_maybe((!labels.is_empty()).then(|| text(labels).size(12)))
+                        .width(Length::Fill),
+                    text(format_duration(entry.task.spent())),
+                    button("Restore").on_press(Message::Restore(index)),
+                ]
+                .spacing(10)
+                .align_y(Center)
+                .into()
+            })
+            .collect();
+
+        let list = if rows.is_empty() {
+            column![text("No archived tasks found.")]
+        } else {
+            column(rows).spacing(5)
+        };
+
+        container(
+            column![
+                text("Archive").size(24),
+                horizontal_rule(1),
+                text_input("Search archived tasks...", &self.search).on_input(Message::Search),
+                scrollable(list).height(Length::Fill).spacing(5),
+            ]
+            .spacing(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(15)
+        .into()
+    }
+}
+
+/// Formats a `Duration` into an `HH:MM:SS` string.
+fn format_duration(duration: Duration) -> String {
+    let total_secs = duration.as_secs();
+    let hours = total_secs / 3600;
+    let minutes = (total_secs % 3600) / 60;
+    let seconds = total_secs % 60;
+    format!("{hours:0>2}:{minutes:0>2}:{seconds:0>2}")
+}
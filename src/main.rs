@@ -5,19 +5,26 @@
 //! The application follows the Elm architecture, where the state is updated via messages,
 //! and the view displays the UI interface from the current state.
 
+mod archive;
+mod notification;
 mod persistence;
 mod pomodoro;
+mod recurrence;
 mod report;
+mod session_log;
 mod setting;
+mod timer;
 
 use iced::keyboard::key::{Key, Named};
-use iced::widget::{column, container, image, row, text};
+use iced::time::{Duration, Instant};
+use iced::widget::{button, column, container, image, row, text};
 use iced::{Element, Length, Subscription, Theme};
 use iced_aw::{TabLabel, Tabs};
 
+use archive::Archive;
 use pomodoro::Pomodoro;
 use report::Report;
-use setting::Settings;
+use setting::Profiles;
 
 fn main() -> iced::Result {
     iced::application("Icemodoro", App::update, App::view)
@@ -27,12 +34,31 @@ fn main() -> iced::Result {
         .run()
 }
 
+/// How long the toast offering to undo a deletion stays visible.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Most changes that can be undone.
+const HISTORY_LIMIT: usize = 50;
+
 /// Holds the entire state of the application, including the state for each tab.
 struct App {
     active_tab: TabId,
     pomodoro: Pomodoro,
-    settings: Settings,
+    settings: Profiles,
     report: Report,
+    archive: Archive,
+    /// States before the changes that can be undone, most recent last.
+    undo: Vec<Snapshot>,
+    /// States before the undone changes, most recent last.
+    redo: Vec<Snapshot>,
+    /// The toast offering to undo the last deletion, with the time it appeared.
+    toast: Option<(&'static str, Instant)>,
+}
+
+/// A change to the task list, or a state of the report, that undo and redo bring back.
+enum Snapshot {
+    Tasks(pomodoro::Change),
+    Report(Report),
 }
 
 /// Defines all messages that can update the application's state.
@@ -46,6 +72,10 @@ enum Message {
     Pomodoro(pomodoro::Message),
     Settings(setting::Message),
     Report(report::Message),
+    Archive(archive::Message),
+    Undo,
+    Redo,
+    ToastTick,
 }
 
 /// Identifier for each application tab.
@@ -54,25 +84,26 @@ enum TabId {
     Pomodoro,
     Setting,
     Report,
+    Archive,
 }
 
 impl Default for App {
     fn default() -> Self {
-        let settings = Settings::new();
-        let pomodoro = Pomodoro::new(
-            settings.work_min,
-            settings.break_min,
-            settings.long_break_min,
-            settings.long_break_after,
-            settings.work_theme.to_iced_theme(),
-            settings.break_theme.to_iced_theme(),
-        );
+        let settings = Profiles::new();
+        let active = settings.active();
+        let pomodoro = Pomodoro::new(active.pomodoro_config());
+        let mut report = Report::new();
+        report.set_streak_goal_only(active.goal.streak_goal_only);
 
         Self {
             pomodoro,
             settings,
             active_tab: TabId::Pomodoro,
-            report: Report::new(),
+            report,
+            archive: Archive::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            toast: None,
         }
     }
 }
@@ -89,48 +120,129 @@ impl App {
                 self.active_tab = match self.active_tab {
                     TabId::Pomodoro => TabId::Setting,
                     TabId::Setting => TabId::Report,
-                    TabId::Report => TabId::Pomodoro,
+                    TabId::Report => TabId::Archive,
+                    TabId::Archive => TabId::Pomodoro,
                 };
             }
             Message::NavigateTabBackward => {
                 self.active_tab = match self.active_tab {
-                    TabId::Pomodoro => TabId::Report,
+                    TabId::Pomodoro => TabId::Archive,
                     TabId::Setting => TabId::Pomodoro,
                     TabId::Report => TabId::Setting,
+                    TabId::Archive => TabId::Report,
                 };
             }
             Message::Pomodoro(p_msg) => {
-                // When a pomodoro day ends, generate a report and switch to the report tab.
+                // When a pomodoro day ends, generate a report and switch to the report tab,
+                // then archive the completed tasks before they are removed from the list.
+                // Undo would bring back tasks that are already reported and archived, so the
+                // history starts over.
                 if matches!(p_msg, pomodoro::Message::EndDay) {
-                    let (focused, completed) = self.pomodoro.get_completed_stats();
-                    if completed > 0 {
-                        self.report
-                            .update(report::Message::Generate { focused, completed });
+                    self.undo.clear();
+                    self.redo.clear();
+                    self.toast = None;
+                    let summary = self.pomodoro.get_completed_stats();
+                    if !summary.is_empty() {
+                        self.report.update(report::Message::Generate(summary));
                         self.active_tab = TabId::Report;
                     }
+                    let today = chrono::Local::now().date_naive();
+                    self.archive.add(self.pomodoro.completed_tasks(), today);
                 }
 
-                return self.pomodoro.update(p_msg).map(Message::Pomodoro);
+                let toast = p_msg.undo_toast();
+                let task = self.pomodoro.update(p_msg).map(Message::Pomodoro);
+                if let Some(change) = self.pomodoro.take_change() {
+                    self.record(Snapshot::Tasks(change), toast);
+                }
+                return task;
             }
             Message::Settings(s_msg) => {
-                // When settings are submitted, apply them and switch to pomodoro tab.
-                if matches!(s_msg, setting::Message::Submit) {
-                    self.pomodoro.apply_settings(
-                        self.settings.work_min,
-                        self.settings.break_min,
-                        self.settings.work_theme.to_iced_theme(),
-                        self.settings.break_theme.to_iced_theme(),
-                    );
+                let submitted = matches!(s_msg, setting::Message::Submit);
+                let previous = self.settings.active().clone();
+                self.settings.update(s_msg);
+
+                // Apply every change right away, whether it was made in the settings tab,
+                // by picking another profile, or by editing the settings file.
+                if *self.settings.active() != previous {
+                    let active = self.settings.active();
+                    self.pomodoro.apply_settings(active.pomodoro_config());
+                    self.report
+                        .set_streak_goal_only(active.goal.streak_goal_only);
+                }
+                // After submitting, switch to pomodoro tab.
+                if submitted {
                     self.active_tab = TabId::Pomodoro;
                 }
-                self.settings.update(s_msg);
             }
-            Message::Report(r_msg) => self.report.update(r_msg),
+            Message::Report(r_msg) => {
+                if matches!(r_msg, report::Message::Clear) {
+                    self.record(
+                        Snapshot::Report(self.report.clone()),
+                        Some("Report history cleared"),
+                    );
+                }
+                self.report.update(r_msg);
+            }
+            Message::Archive(a_msg) => {
+                if let Some(task) = self.archive.update(a_msg) {
+                    self.pomodoro.restore_task(task);
+                }
+            }
+            Message::Undo => {
+                if let Some(snapshot) = self.undo.pop()
+                    && let Some(current) = self.restore(snapshot)
+                {
+                    self.redo.push(current);
+                }
+                self.toast = None;
+            }
+            Message::Redo => {
+                if let Some(snapshot) = self.redo.pop()
+                    && let Some(current) = self.restore(snapshot)
+                {
+                    self.undo.push(current);
+                }
+                self.toast = None;
+            }
+            Message::ToastTick => {
+                if self
+                    .toast
+                    .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+                {
+                    self.toast = None;
+                }
+            }
         }
 
         iced::Task::none()
     }
 
+    /// Records the state before a change so it can be undone, and shows the toast if given.
+    fn record(&mut self, snapshot: Snapshot, toast: Option<&'static str>) {
+        self.undo.push(snapshot);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        if let Some(toast) = toast {
+            self.toast = Some((toast, Instant::now()));
+        }
+    }
+
+    /// Undoes a recorded change or brings back a recorded state, and returns what redoes it,
+    /// `None` if the tasks it touched are gone.
+    fn restore(&mut self, snapshot: Snapshot) -> Option<Snapshot> {
+        match snapshot {
+            Snapshot::Tasks(change) => self.pomodoro.undo(change).map(Snapshot::Tasks),
+            Snapshot::Report(report) => {
+                let current = self.report.clone();
+                self.report.restore(report);
+                Some(Snapshot::Report(current))
+            }
+        }
+    }
+
     /// Defines application-wide subscriptions for timers and keyboard events.
     fn subscription(&self) -> Subscription<Message> {
         let pomodoro_sub = self.pomodoro.subscription().map(Message::Pomodoro);
@@ -138,10 +250,26 @@ impl App {
         let tab_sub = iced::keyboard::on_key_press(|key, modifiers| match key.as_ref() {
             Key::Named(Named::Tab) if modifiers.shift() => Some(Message::NavigateTabBackward),
             Key::Named(Named::Tab) if modifiers.control() => Some(Message::NavigateTabForward),
+            Key::Character("p") if modifiers.control() => {
+                Some(Message::Settings(setting::Message::NextProfile))
+            }
+            Key::Character("z" | "Z") if modifiers.control() && modifiers.shift() => {
+                Some(Message::Redo)
+            }
+            Key::Character("z" | "Z") if modifiers.control() => Some(Message::Undo),
             _ => None,
         });
 
-        Subscription::batch(vec![pomodoro_sub, tab_sub])
+        let settings_sub = Profiles::subscription().map(Message::Settings);
+
+        // Hide the undo toast once it has been visible long enough.
+        let toast_sub = if self.toast.is_some() {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::ToastTick)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(vec![pomodoro_sub, settings_sub, tab_sub, toast_sub])
     }
 
     /// Constructs the user interface from the current application state.
@@ -167,10 +295,25 @@ impl App {
                 TabLabel::Text("Report".to_string()),
                 self.report.view().map(Message::Report),
             )
+            .push(
+                TabId::Archive,
+                TabLabel::Text("Archive".to_string()),
+                self.archive.view().map(Message::Archive),
+            )
             .set_active_tab(&self.active_tab)
             .tab_label_spacing(10);
 
-        container(column![header, tabs].width(450))
+        let toast = self.toast.map(|(toast, _)| {
+            row![
+                text(toast).width(Length::Fill),
+                button("Undo").on_press(Message::Undo),
+            ]
+            .spacing(10)
+            .padding(10)
+            .align_y(iced::Center)
+        });
+
+        container(column![header].push_maybe(toast).push(tabs).width(450))
             .center_x(Length::Fill)
             .padding(10)
             .into()
@@ -0,0 +1,609 @@
+//! Manages the desktop notification messages, loaded from a user-editable file with
+//! per-language and per-session pools, falling back to the built-in messages, and shows
+//! notifications with buttons that drive the timer, configured separately for each event.
+
+use super::persistence;
+use super::timer::{Session, Warning};
+
+use iced::time::Duration;
+
+use notify_rust::Notification;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+/// Name of the user-editable file holding the message pools of each language.
+const MESSAGES_FILE: &str = "messages.json";
+
+/// Language of the built-in messages.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+const BREAK_SUMMARIES: [&str; 7] = [
+    "Stretch up high, touch the sky. Take a sip, stay fresh and spry.",
+    "Bend and sway, greet the day. Drink your water, wash fatigue away.",
+    "Twist with grace, find your space. Sip some water, keep your pace.",
+    "Reach and glide, open wide. Hydrate well, feel joy inside.",
+    "Roll your neck, take a sec. Drink your water, keep your check.",
+    "Stretch with cheer, far and near. Take a sip, refresh your gear.",
+    "Wiggle free, breathe with glee. Sip your water, let it be.",
+];
+
+const LONG_BREAK_SUMMARIES: [&str; 4] = [
+    "Cycle done, you've earned the fun. Step outside and greet the sun.",
+    "Set of four, now rest some more. Take a walk out the door.",
+    "Great long run, your work is spun. Grab a snack, recharge for one.",
+    "Screens away, go out and play. Fresh air will brighten your day.",
+];
+
+const WORK_SUMMARIES: [&str; 7] = [
+    "Shake off breaks and take your seat, make your tasks feel light and neat.",
+    "Stretch was sweet, now tap your keys, move with calm and gentle ease.",
+    "Sip was done, now face the day, let your work flow in a playful way.",
+    "Shake the rest from head to toe, dive in now and let ideas grow.",
+    "Mind refreshed, body bright, tackle tasks with all your might.",
+    "Take a breath, then start the grind, joy and focus you will find.",
+    "Break is gone, energy’s prime, back to work, it’s task time!",
+];
+
+/// Events that send a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// A session ran out.
+    SessionEnd,
+    /// A session ran out and a long break is next.
+    LongBreak,
+    /// A session has been in overtime for a while.
+    Overtime,
+    /// A session is about to run out.
+    Warning,
+}
+
+impl Trigger {
+    /// List of all events, used for listing their settings.
+    pub const ALL: [Trigger; 4] = [
+        Trigger::SessionEnd,
+        Trigger::LongBreak,
+        Trigger::Overtime,
+        Trigger::Warning,
+    ];
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SessionEnd => write!(f, "Session end"),
+            Self::LongBreak => write!(f, "Long break start"),
+            Self::Overtime => write!(f, "Overtime reminder"),
+            Self::Warning => write!(f, "Session end warning"),
+        }
+    }
+}
+
+/// How urgent a notification is, which decides how the desktop presents it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    /// List of all urgency levels, used for picking one.
+    pub const ALL: [Urgency; 3] = [Urgency::Low, Urgency::Normal, Urgency::Critical];
+}
+
+impl std::fmt::Display for Urgency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "Low"),
+            Self::Normal => write!(f, "Normal"),
+            Self::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+/// How the notification of a single event is shown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Behavior {
+    pub enabled: bool,
+    /// Name of a sound from the desktop's sound theme or a path to a sound file, empty for none.
+    pub sound: String,
+    pub urgency: Urgency,
+    /// Seconds until the notification disappears, zero for the desktop's default.
+    pub timeout_sec: u8,
+}
+
+impl Default for Behavior {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sound: "alarm-clock-elapsed".to_string(),
+            urgency: Urgency::Normal,
+            timeout_sec: 0,
+        }
+    }
+}
+
+impl Behavior {
+    /// Creates a notification with the given summary, shown the configured way.
+    pub fn notification(&self, summary: &str) -> Notification {
+        let mut notification = Notification::new();
+        notification.summary(summary);
+        if self.timeout_sec > 0 {
+            notification.timeout(Duration::from_secs(u64::from(self.timeout_sec)));
+        }
+        self.apply_sound_and_urgency(&mut notification);
+        notification
+    }
+
+    /// Sets the sound, either from a file or from the sound theme, and the urgency.
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn apply_sound_and_urgency(&self, notification: &mut Notification) {
+        let is_file = self.sound.contains(std::path::MAIN_SEPARATOR);
+        if is_file {
+            notification.hint(notify_rust::Hint::SoundFile(self.sound.clone()));
+        } else if !self.sound.is_empty() {
+            notification.sound_name(&self.sound);
+        }
+        notification.urgency(match self.urgency {
+            Urgency::Low => notify_rust::Urgency::Low,
+            Urgency::Normal => notify_rust::Urgency::Normal,
+            Urgency::Critical => notify_rust::Urgency::Critical,
+        });
+    }
+
+    /// Sets the sound from the system sounds; other desktops have no urgency levels.
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    fn apply_sound_and_urgency(&self, notification: &mut Notification) {
+        if !self.sound.is_empty() {
+            notification.sound_name(&self.sound);
+        }
+    }
+}
+
+/// A heads-up sent the given number of seconds before a session of the given type runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarningSetting {
+    pub session: Session,
+    pub seconds: u16,
+}
+
+/// Notification settings of every event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Alerts {
+    pub session_end: Behavior,
+    pub long_break: Behavior,
+    pub overtime: Behavior,
+    pub warning: Behavior,
+    /// Minutes of overtime before the overtime reminder is sent.
+    pub overtime_after_min: u8,
+    /// Whether the overtime reminder is sent again after every `overtime_after_min`.
+    pub overtime_repeat: bool,
+    /// Heads-ups before sessions run out.
+    pub warnings: Vec<WarningSetting>,
+    /// The timer changes colour in the last seconds of a session, zero to keep its colour.
+    pub highlight_sec: u16,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            session_end: Behavior::default(),
+            long_break: Behavior::default(),
+            overtime: Behavior {
+                sound: "bell".to_string(),
+                ..Behavior::default()
+            },
+            warning: Behavior {
+                sound: "message".to_string(),
+                ..Behavior::default()
+            },
+            overtime_after_min: 5,
+            overtime_repeat: false,
+            warnings: Vec::new(),
+            highlight_sec: 0,
+        }
+    }
+}
+
+impl Alerts {
+    /// Returns the notification settings of the given event.
+    pub fn get(&self, trigger: Trigger) -> &Behavior {
+        match trigger {
+            Trigger::SessionEnd => &self.session_end,
+            Trigger::LongBreak => &self.long_break,
+            Trigger::Overtime => &self.overtime,
+            Trigger::Warning => &self.warning,
+        }
+    }
+
+    /// Returns the notification settings of the given event for editing.
+    pub fn get_mut(&mut self, trigger: Trigger) -> &mut Behavior {
+        match trigger {
+            Trigger::SessionEnd => &mut self.session_end,
+            Trigger::LongBreak => &mut self.long_break,
+            Trigger::Overtime => &mut self.overtime,
+            Trigger::Warning => &mut self.warning,
+        }
+    }
+
+    /// Returns the heads-ups used by the timer, none if their notification is turned off.
+    pub fn warnings(&self) -> Vec<Warning> {
+        if !self.warning.enabled {
+            return Vec::new();
+        }
+        self.warnings
+            .iter()
+            .map(|warning| Warning {
+                session: warning.session,
+                before: Duration::from_secs(u64::from(warning.seconds)),
+            })
+            .collect()
+    }
+
+    /// Returns how many overtime reminders are due after the given overtime.
+    pub fn reminders_due(&self, overtime: Duration) -> u64 {
+        let every = u64::from(self.overtime_after_min) * 60;
+        if !self.overtime.enabled || every == 0 {
+            return 0;
+        }
+        let due = overtime.as_secs() / every;
+        if self.overtime_repeat {
+            due
+        } else {
+            due.min(1)
+        }
+    }
+}
+
+/// Buttons offered on a notification when a session expires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    StartBreak,
+    SkipBreak,
+    Extend,
+    StartPomodoro,
+}
+
+impl Action {
+    const ALL: [Action; 4] = [
+        Action::StartBreak,
+        Action::SkipBreak,
+        Action::Extend,
+        Action::StartPomodoro,
+    ];
+
+    /// Returns the identifier sent back by the notification server when the button is clicked.
+    fn id(self) -> &'static str {
+        match self {
+            Self::StartBreak => "start-break",
+            Self::SkipBreak => "skip-break",
+            Self::Extend => "extend",
+            Self::StartPomodoro => "start-pomodoro",
+        }
+    }
+
+    /// Returns the text shown on the button.
+    fn label(self) -> &'static str {
+        match self {
+            Self::StartBreak => "Start break",
+            Self::SkipBreak => "Skip break",
+            Self::Extend => "+5 minutes",
+            Self::StartPomodoro => "Start next pomodoro",
+        }
+    }
+
+    /// Finds the action belonging to the identifier, `None` if the notification was closed.
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+}
+
+/// Shows the notification with a button for each action and resolves to the clicked one.
+///
+/// Also returns the ID of the notification while it waits for a click, which should be passed
+/// to [`close`] once its buttons are no longer needed.
+pub fn show(
+    notification: &mut Notification,
+    actions: &[Action],
+) -> (Option<u32>, iced::Task<Action>) {
+    for action in actions {
+        notification.action(action.id(), action.label());
+    }
+    match notification.show() {
+        Ok(handle) if !actions.is_empty() => wait_for_action(handle),
+        _ => (None, iced::Task::none()),
+    }
+}
+
+/// Resolves to the action the user clicked on the shown notification.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn wait_for_action(handle: notify_rust::NotificationHandle) -> (Option<u32>, iced::Task<Action>) {
+    use iced::futures::channel::oneshot;
+
+    // Waiting for the click blocks until the notification is closed, so it is done on its
+    // own thread.
+    let id = handle.id();
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(wait_for_click(handle));
+    });
+    let task = iced::Task::perform(receiver, Result::ok)
+        .and_then(|action| action.map_or_else(iced::Task::none, iced::Task::done));
+    (Some(id), task)
+}
+
+/// Blocks until a button of the notification is clicked or the notification is closed.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn wait_for_click(handle: notify_rust::NotificationHandle) -> Option<Action> {
+    let mut clicked = None;
+    handle.wait_for_action(|id| clicked = Action::from_id(id));
+    clicked
+}
+
+/// Closes the notification with the given ID, which ends the wait for its buttons.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn close(id: u32) {
+    // The notification may already be gone, e.g. closed by the user.
+    let _ = zbus::blocking::Connection::session().and_then(|connection| {
+        connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "CloseNotification",
+            &id,
+        )
+    });
+}
+
+/// Buttons are only reported back by the notification servers of Linux and BSD desktops.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn wait_for_action<H>(_handle: H) -> (Option<u32>, iced::Task<Action>) {
+    (None, iced::Task::none())
+}
+
+/// Nothing waits for the buttons on other desktops, so there is nothing to close.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn close(_id: u32) {}
+
+/// Notification messages of a single language, grouped by the session that is about to start.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessagePool {
+    pub work: Vec<String>,
+    pub short_break: Vec<String>,
+    pub long_break: Vec<String>,
+}
+
+impl MessagePool {
+    /// Creates the pool of built-in English messages.
+    fn builtin() -> Self {
+        let to_strings = |summaries: &[&str]| summaries.iter().map(ToString::to_string).collect();
+        Self {
+            work: to_strings(&WORK_SUMMARIES),
+            short_break: to_strings(&BREAK_SUMMARIES),
+            long_break: to_strings(&LONG_BREAK_SUMMARIES),
+        }
+    }
+
+    /// Returns the messages announcing the given session.
+    fn get(&self, session: Session) -> &[String] {
+        match session {
+            Session::Pomodoro => &self.work,
+            Session::Break => &self.short_break,
+            Session::LongBreak => &self.long_break,
+        }
+    }
+}
+
+/// Message pools of all languages.
+#[derive(Debug, Clone)]
+pub struct Messages {
+    /// Pools keyed by language code (e.g. `en` or `hu`).
+    pools: BTreeMap<String, MessagePool>,
+    /// Built-in pool used for missing languages or empty pools.
+    fallback: MessagePool,
+    /// Whether the pools were loaded from the user's file.
+    from_file: bool,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            pools: BTreeMap::from([(DEFAULT_LANGUAGE.to_string(), MessagePool::builtin())]),
+            fallback: MessagePool::builtin(),
+            from_file: false,
+        }
+    }
+}
+
+impl Messages {
+    /// Loads the message pools from the data directory, or uses the built-in ones
+    /// if the file is missing or invalid.
+    pub fn load() -> Self {
+        match persistence::load::<BTreeMap<String, MessagePool>>(MESSAGES_FILE) {
+            Ok(pools) if !pools.is_empty() => Self {
+                pools,
+                from_file: true,
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Returns the codes of all available languages.
+    pub fn languages(&self) -> Vec<String> {
+        self.pools.keys().cloned().collect()
+    }
+
+    /// Picks a random message announcing the given session in the given language.
+    pub fn pick(&self, language: &str, session: Session) -> String {
+        let pool = self
+            .pools
+            .get(language)
+            .map(|pool| pool.get(session))
+            .filter(|messages| !messages.is_empty())
+            .unwrap_or_else(|| self.fallback.get(session));
+
+        let index = rand::rng().random_range(0..pool.len());
+        pool[index].clone()
+    }
+
+    /// Describes the pool in use for the given language, e.g. for showing it in the settings.
+    pub fn describe(&self, language: &str) -> String {
+        let source = if self.from_file {
+            MESSAGES_FILE
+        } else {
+            "built-in messages"
+        };
+        match self.pools.get(language) {
+            Some(pool) => format!(
+                "{source} ({language}): {} work, {} break, {} long break messages",
+                pool.work.len(),
+                pool.short_break.len(),
+                pool.long_break.len()
+            ),
+            None => format!("{source}: no '{language}' messages, using built-in ones"),
+        }
+    }
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+// The generated D-Bus method handlers pass on the arguments the stand-in daemon ignores.
+#[allow(clippy::used_underscore_binding)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::process::Command;
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    use zbus::object_server::SignalEmitter;
+    use zbus::zvariant::OwnedValue;
+
+    /// Set in the copy of the test process that runs inside the private session bus.
+    const PRIVATE_BUS: &str = "ICEMODORO_PRIVATE_BUS";
+    const NAME: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+
+    /// Stand-in notification daemon that accepts every notification.
+    #[derive(Default)]
+    struct Daemon {
+        last_id: u32,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Daemon {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &mut self,
+            _app_name: String,
+            _replaces_id: u32,
+            _app_icon: String,
+            _summary: String,
+            _body: String,
+            _actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            self.last_id += 1;
+            self.last_id
+        }
+
+        async fn close_notification(
+            &self,
+            id: u32,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<()> {
+            // Reason 3 means the notification was closed by a call to `CloseNotification`.
+            Self::notification_closed(&emitter, id, 3).await?;
+            Ok(())
+        }
+
+        #[zbus(signal)]
+        async fn notification_closed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            reason: u32,
+        ) -> zbus::Result<()>;
+    }
+
+    /// Runs the test again inside a private session bus, so no real desktop is involved.
+    /// Returns `false` in the copy of the test that should do the actual work.
+    fn rerun_in_private_bus(test: &str) -> bool {
+        if std::env::var_os(PRIVATE_BUS).is_some() {
+            return false;
+        }
+        let status = Command::new("dbus-run-session")
+            .arg("--")
+            .arg(std::env::current_exe().unwrap())
+            .args([test, "--exact", "--test-threads=1"])
+            .env(PRIVATE_BUS, "1")
+            .status();
+        match status {
+            Ok(status) => assert!(status.success(), "{test} failed inside the private bus"),
+            Err(error) => eprintln!("skipping {test}, dbus-run-session is not available: {error}"),
+        }
+        true
+    }
+
+    fn start_daemon() -> zbus::blocking::Connection {
+        zbus::blocking::connection::Builder::session()
+            .unwrap()
+            .name(NAME)
+            .unwrap()
+            .serve_at(PATH, Daemon::default())
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    /// Shows a notification with a button and waits for the click on its own thread, poking
+    /// the daemon with the notification's ID until the wait ends.
+    fn wait_while(poke: impl Fn(u32)) -> Option<Action> {
+        let handle = Notification::new()
+            .summary("Pomodoro is over")
+            .action(Action::Extend.id(), Action::Extend.label())
+            .show()
+            .unwrap();
+        let id = handle.id();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || sender.send(wait_for_click(handle)));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            poke(id);
+            if let Ok(clicked) = receiver.recv_timeout(Duration::from_millis(100)) {
+                return clicked;
+            }
+        }
+        panic!("the wait for a click did not end");
+    }
+
+    #[test]
+    fn close_ends_the_wait_for_a_click() {
+        if rerun_in_private_bus("notification::tests::close_ends_the_wait_for_a_click") {
+            return;
+        }
+        let _daemon = start_daemon();
+        assert_eq!(wait_while(close), None);
+    }
+
+    #[test]
+    fn clicked_button_is_reported() {
+        if rerun_in_private_bus("notification::tests::clicked_button_is_reported") {
+            return;
+        }
+        let daemon = start_daemon();
+        let clicked = wait_while(|id| {
+            daemon
+                .emit_signal(None::<()>, PATH, NAME, "ActionInvoked", &(id, "extend"))
+                .unwrap();
+        });
+        assert_eq!(clicked, Some(Action::Extend));
+    }
+}
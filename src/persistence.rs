@@ -1,4 +1,4 @@
-//! Handles saving, loading, and managing application data files (settings and reports)
+//! Handles saving, loading, and managing application data files (settings, reports, and logs)
 //! using JSON serialization and adhering to platform-specific data directory standards.
 
 use directories_next::ProjectDirs;
@@ -6,9 +6,10 @@ use rfd::FileDialog;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Convenience alias for persistence-related results.
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -38,8 +39,22 @@ pub fn save<T: Serialize>(filename: &str, data: &T) -> Result<()> {
     Ok(())
 }
 
+/// Appends serializable data as a single JSON line to a file in the app's data directory.
+pub fn append<T: Serialize>(filename: &str, data: &T) -> Result<()> {
+    let mut path = get_app_data_dir()?;
+    path.push(filename);
+
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, data)?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
 /// Loads data from a JSON file in the app's data directory.
-pub fn load<T: DeserializeOwned + Default>(filename: &str) -> Result<T> {
+pub fn load<T: DeserializeOwned>(filename: &str) -> Result<T> {
     let mut path = get_app_data_dir()?;
     path.push(filename);
 
@@ -50,6 +65,14 @@ pub fn load<T: DeserializeOwned + Default>(filename: &str) -> Result<T> {
     Ok(data)
 }
 
+/// Returns when a file in the app's data directory was last modified.
+pub fn modified(filename: &str) -> Result<SystemTime> {
+    let mut path = get_app_data_dir()?;
+    path.push(filename);
+
+    Ok(fs::metadata(path)?.modified()?)
+}
+
 /// Exports serializable data to a user-chosen file anywhere via save file dialog.
 pub fn export<T: Serialize>(data: &T) -> Result<()> {
     let path = FileDialog::new()
@@ -1,38 +1,32 @@
 //! Manages the core Pomodoro timer, session state, and task list functionality.
 
+use super::notification::{self, Action, Alerts, Messages};
 use super::persistence;
+use super::recurrence::{Recurrence, RecurrenceKind, WEEK};
+use super::report::{DaySummary, Goal, SeriesTime};
+use super::session_log::{self, SessionRecord};
+use super::timer::{
+    AutoStart, BreakRule, Event, Interruption, Interruptions, OvertimePolicy, Session, Spent,
+    State, Step, SystemClock, TimerEngine,
+};
 
 use iced::keyboard::key::{Key, Named};
-use iced::time::{Duration, Instant};
+use iced::time::Duration;
 use iced::widget::text_input::Id;
 use iced::widget::{
-    button, column, container, horizontal_rule, keyed_column, progress_bar, row, text, text_input,
+    button, checkbox, column, container, horizontal_rule, keyed_column, pick_list, progress_bar,
+    row, scrollable, text, text_editor, text_input,
 };
 use iced::{Center, Element, Length, Subscription, Theme};
+use iced_aw::widget::number_input;
 
-use notify_rust::Notification;
-use rand::Rng;
+use chrono::{Local, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 
-const BREAK_SUMMARIES: [&str; 7] = [
-    "Stretch up high, touch the sky. Take a sip, stay fresh and spry.",
-    "Bend and sway, greet the day. Drink your water, wash fatigue away.",
-    "Twist with grace, find your space. Sip some water, keep your pace.",
-    "Reach and glide, open wide. Hydrate well, feel joy inside.",
-    "Roll your neck, take a sec. Drink your water, keep your check.",
-    "Stretch with cheer, far and near. Take a sip, refresh your gear.",
-    "Wiggle free, breathe with glee. Sip your water, let it be.",
-];
-
-const WORK_SUMMARIES: [&str; 7] = [
-    "Shake off breaks and take your seat, make your tasks feel light and neat.",
-    "Stretch was sweet, now tap your keys, move with calm and gentle ease.",
-    "Sip was done, now face the day, let your work flow in a playful way.",
-    "Shake the rest from head to toe, dive in now and let ideas grow.",
-    "Mind refreshed, body bright, tackle tasks with all your might.",
-    "Take a breath, then start the grind, joy and focus you will find.",
-    "Break is gone, energy’s prime, back to work, it’s task time!",
-];
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Extra time given to an expired session from its notification.
+const EXTENSION: Duration = Duration::from_mins(5);
 
 /// Represents a single task in the to-do list.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,62 +35,455 @@ pub struct Task {
     desc: String,
     spent: Duration,
     done: bool,
+    #[serde(default)]
+    interruptions: Interruptions,
+    /// Time logged after sessions reached zero, also included in `spent` if it counts as focus.
+    #[serde(default)]
+    overtime: Duration,
+    /// Part of the overtime included in `spent`.
+    #[serde(default)]
+    counted_overtime: Duration,
+    /// Number of pomodoros the task is expected to take, zero if not estimated.
+    #[serde(default)]
+    estimate: u32,
+    /// Number of work sessions finished while the task was active.
+    #[serde(default)]
+    pomodoros: u32,
+    /// Ordered checklist of smaller steps, whose time is logged on this task.
+    #[serde(default)]
+    subtasks: Vec<Subtask>,
+    /// Project or client the time is attributed to, written as `@project`.
+    #[serde(default)]
+    project: Option<String>,
+    /// Labels written as `#tag`.
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Priority,
+    /// Free-form context such as links, acceptance criteria, or where the work was left off.
+    #[serde(default)]
+    notes: String,
+    /// When the task comes due again after it was completed, `None` if it does not recur.
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
+    /// Identifies the occurrences of a recurring task, so their time adds up in the report.
+    #[serde(default)]
+    series: Option<u64>,
+    /// The day the task was completed, `None` while it is not done.
+    #[serde(default)]
+    completed_on: Option<NaiveDate>,
+}
+
+/// A completed recurring task, waiting to reappear on the day it comes due.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Upcoming {
+    due: NaiveDate,
+    task: Task,
+}
+
+/// How urgent a task is, shown as exclamation marks next to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    const ALL: [Self; 4] = [Self::None, Self::Low, Self::Medium, Self::High];
+
+    /// Returns the next higher priority, wrapping around to none after high.
+    fn next(self) -> Self {
+        match self {
+            Self::None => Self::Low,
+            Self::Low => Self::Medium,
+            Self::Medium => Self::High,
+            Self::High => Self::None,
+        }
+    }
+
+    /// Returns the exclamation marks shown next to the task.
+    fn marker(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Low => "!",
+            Self::Medium => "!!",
+            Self::High => "!!!",
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "No priority",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The order the task list can be sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Highest priority first.
+    Priority,
+    /// Most time spent first.
+    Spent,
+    /// Oldest task first.
+    Created,
+}
+
+impl SortKey {
+    const ALL: [Self; 3] = [Self::Priority, Self::Spent, Self::Created];
+}
+
+impl std::fmt::Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Priority => "Priority",
+            Self::Spent => "Time spent",
+            Self::Created => "Creation time",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Narrows the task list down to a project or a tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    All,
+    Project(String),
+    Tag(String),
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "All tasks"),
+            Self::Project(project) => write!(f, "@{project}"),
+            Self::Tag(tag) => write!(f, "#{tag}"),
+        }
+    }
+}
+
+/// A checklist item inside a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Subtask {
+    desc: String,
+    done: bool,
 }
 
 impl Task {
-    fn new(id: u64, desc: String) -> Self {
+    /// Creates a task from the quick-add text, see [`parse_labels`].
+    fn new(id: u64, input: &str) -> Self {
+        let (desc, project, tags) = parse_labels(input);
         Self {
             id,
             desc,
             spent: Duration::ZERO,
             done: false,
+            interruptions: Interruptions::default(),
+            overtime: Duration::ZERO,
+            counted_overtime: Duration::ZERO,
+            estimate: 0,
+            pomodoros: 0,
+            subtasks: Vec::new(),
+            project,
+            tags,
+            priority: Priority::None,
+            notes: String::new(),
+            recurrence: None,
+            series: None,
+            completed_on: None,
+        }
+    }
+
+    /// Returns the task undone, with its logged time and interruptions starting over.
+    fn reset(self) -> Self {
+        Self {
+            spent: Duration::ZERO,
+            done: false,
+            interruptions: Interruptions::default(),
+            overtime: Duration::ZERO,
+            counted_overtime: Duration::ZERO,
+            pomodoros: 0,
+            completed_on: None,
+            ..self
+        }
+    }
+
+    /// Returns the next occurrence of a recurring task, with its subtasks unticked.
+    fn next_occurrence(&self) -> Self {
+        let mut task = self.clone().reset();
+        for subtask in &mut task.subtasks {
+            subtask.done = false;
+        }
+        task
+    }
+
+    /// Formats the project, the tags, and the schedule of a recurring task, shown below its
+    /// description.
+    fn format_details(&self) -> String {
+        let labels = self.format_labels();
+        match &self.recurrence {
+            Some(recurrence) if labels.is_empty() => format!("↻ {recurrence}"),
+            Some(recurrence) => format!("{labels} ↻ {recurrence}"),
+            None => labels,
+        }
+    }
+
+    /// Returns the description of the task.
+    pub fn desc(&self) -> &str {
+        &self.desc
+    }
+
+    /// Returns the focused time logged on the task.
+    pub fn spent(&self) -> Duration {
+        self.spent
+    }
+
+    /// Puts the edited details on the task and returns the ones it had.
+    fn replace_details(&mut self, details: Details) -> Details {
+        Details {
+            desc: std::mem::replace(&mut self.desc, details.desc),
+            project: std::mem::replace(&mut self.project, details.project),
+            tags: std::mem::replace(&mut self.tags, details.tags),
+            notes: std::mem::replace(&mut self.notes, details.notes),
+        }
+    }
+
+    /// Returns the day the task was completed, `None` while it is not done.
+    pub fn completed_on(&self) -> Option<NaiveDate> {
+        self.completed_on
+    }
+
+    /// Formats the project and the tags the way they are typed, e.g. `@acme #backend`.
+    pub fn format_labels(&self) -> String {
+        self.project
+            .iter()
+            .map(|project| format!("@{project}"))
+            .chain(self.tags.iter().map(|tag| format!("#{tag}")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns whether the description, notes, project, or tags contain the search text,
+    /// ignoring case.
+    pub fn matches_search(&self, search: &str) -> bool {
+        let search = search.trim().to_lowercase();
+        search.is_empty()
+            || [&self.desc, &self.notes, &self.format_labels()]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&search))
+    }
+
+    /// Returns whether the task is shown with the filter.
+    fn matches(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::All => true,
+            Filter::Project(project) => self.project.as_ref() == Some(project),
+            Filter::Tag(tag) => self.tags.contains(tag),
+        }
+    }
+
+    /// Formats the ticked subtasks out of all subtasks, e.g. `☑ 2/5`.
+    fn format_subtasks(&self) -> String {
+        if self.subtasks.is_empty() {
+            return String::new();
+        }
+        let done = self.subtasks.iter().filter(|subtask| subtask.done).count();
+        format!("☑ {done}/{}", self.subtasks.len())
+    }
+
+    /// Formats the finished pomodoros against the estimate, e.g. `3/4 🍅`.
+    fn format_pomodoros(&self) -> String {
+        match (self.pomodoros, self.estimate) {
+            (0, 0) => String::new(),
+            (done, 0) => format!("{done} 🍅"),
+            (done, estimate) => format!("{done}/{estimate} 🍅"),
         }
     }
 }
 
+/// A change to the task list that can be undone, see [`Pomodoro::undo`].
+///
+/// It holds only what the change touched, so undoing it keeps the time logged since then.
+#[derive(Debug, Clone)]
+pub struct Change(ChangeKind);
+
+#[derive(Debug, Clone)]
+enum ChangeKind {
+    /// Tasks to put back at their former positions, in ascending order.
+    Insert(Vec<(usize, Task)>),
+    /// Tasks to remove by ID.
+    Remove(Vec<u64>),
+    /// Description, labels, and notes to put back on a task.
+    Edit(u64, Details),
+    /// Completion state to put back on a task, together with the active task.
+    Complete {
+        id: u64,
+        done: bool,
+        completed_on: Option<NaiveDate>,
+        active: Option<u64>,
+    },
+}
+
+/// The part of a task changed by editing it.
+#[derive(Debug, Clone)]
+struct Details {
+    desc: String,
+    project: Option<String>,
+    tags: Vec<String>,
+    notes: String,
+}
+
+/// Time logged while no task was active, reported as unassigned when the day ends.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Unassigned {
+    spent: Duration,
+    overtime: Duration,
+    #[serde(default)]
+    counted_overtime: Duration,
+    interruptions: Interruptions,
+}
+
+impl Unassigned {
+    /// Adds the time of a finished work session.
+    fn add(&mut self, spent: Spent) {
+        self.spent = self.spent.saturating_add(spent.focus);
+        self.overtime = self.overtime.saturating_add(spent.overtime);
+        self.counted_overtime = self.counted_overtime.saturating_add(spent.counted);
+    }
+}
+
+/// Work done on the current day, used for the progress toward the daily goal.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Today {
+    date: NaiveDate,
+    pomodoros: u32,
+    focused: Duration,
+}
+
+impl Today {
+    /// Creates the empty record of today.
+    fn new() -> Self {
+        Self {
+            date: Local::now().date_naive(),
+            pomodoros: 0,
+            focused: Duration::ZERO,
+        }
+    }
+
+    /// Returns the record if it is still today's, or starts over on a new day.
+    fn current(self) -> Self {
+        if self.date == Local::now().date_naive() {
+            self
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Adds focused time, and a finished work session if `pomodoro` is set.
+    fn add(&mut self, focused: Duration, pomodoro: bool) {
+        *self = self.current();
+        self.focused = self.focused.saturating_add(focused);
+        if pomodoro {
+            self.pomodoros = self.pomodoros.saturating_add(1);
+        }
+    }
+}
+
+/// Configuration of the timer and notifications, taken from the active settings.
+pub struct Config {
+    /// Steps of the session cycle together with the theme of each step.
+    pub cycle: Vec<(Step, Theme)>,
+    pub auto_start: AutoStart,
+    pub break_rule: BreakRule,
+    pub overtime_policy: OvertimePolicy,
+    /// Language of the notification messages.
+    pub language: String,
+    pub alerts: Alerts,
+    pub goal: Goal,
+    /// Whether a task is completed once all of its subtasks are ticked.
+    pub auto_complete: bool,
+    /// Most tasks the list can hold, `None` for no limit.
+    pub max_tasks: Option<usize>,
+}
+
 /// Holds the state for the main Pomodoro timer and task management view.
 pub struct Pomodoro {
     //-- Settings --//
-    /// Duration of a work session.
-    work_dur: Duration,
-    /// The current number of finished work session.
-    work_count: u8,
-    /// Duration of a break session.
-    break_dur: Duration,
-    /// Duration of long break session.
-    long_break_dur: Duration,
-    /// Number of work session to start long break.
-    long_break_after: u8,
-    /// Theme used during work sessions.
-    work_theme: Theme,
-    /// Theme used during break sessions.
-    break_theme: Theme,
+    /// Theme used during each step of the session cycle.
+    step_themes: Vec<Theme>,
 
     //-- Timer State --//
     /// The currently active theme.
     theme: Theme,
-    /// The time left in the current session.
-    remaining: Duration,
-    /// The duration of the last completed session segment.
-    last_done: Duration,
-    /// Time elapsed after the timer reaches zero.
-    overtime: Duration,
-    /// The timer's operational state (e.g., Idle, Ticking).
-    state: State,
-    /// The current session type (Pomodoro or Break).
-    session: Session,
+    /// The session state machine counting down the current session.
+    timer: TimerEngine,
+    /// Pools of notification messages.
+    messages: Messages,
+    /// Language of the notification messages.
+    language: String,
+    /// How the notification of each event is shown.
+    alerts: Alerts,
+    /// Overtime reminders already sent for the current session.
+    reminders_sent: u64,
+    /// The notification waiting for a click `(session number, notification id)`.
+    notification: Option<(u64, u32)>,
 
     //-- Task State --//
     /// The list of all tasks.
     tasks: Vec<Task>,
+    /// Time logged today without an active task.
+    unassigned: Unassigned,
+    /// Work done today, counted toward the daily goal.
+    today: Today,
+    /// The daily target of pomodoros and focused time.
+    goal: Goal,
+    /// Whether a task is completed once all of its subtasks are ticked.
+    auto_complete: bool,
+    /// Most tasks the list can hold, `None` for no limit.
+    max_tasks: Option<usize>,
     /// The ID to be assigned to the next new task.
     next_id: u64,
     /// The ID of the currently active task, if any.
     active: Option<u64>,
+    /// Completed recurring tasks waiting for their next occurrence.
+    upcoming: Vec<Upcoming>,
     /// The state of the task currently being edited `(id, description)`.
     editing: Option<(u64, String)>,
+    /// The notes of the task currently being edited.
+    notes: text_editor::Content,
+    /// The tasks whose notes are expanded in the list.
+    expanded: BTreeSet<u64>,
     /// A unique ID for the task editing input field.
     edit_id: Id,
+    /// The project or tag the task list is narrowed down to.
+    filter: Filter,
+    /// Text the shown tasks must contain.
+    search: String,
+    /// A unique ID for the task filter input field.
+    search_id: Id,
+    /// Whether completed tasks are shown in the list.
+    show_completed: bool,
+    /// A unique ID for the scrollable task list.
+    list_id: scrollable::Id,
+    /// The subtask selected with the keyboard `(task id, index)`.
+    subtask: Option<(u64, usize)>,
+    /// The subtask being added or edited `(task id, index, description)`, the index is `None`
+    /// for a new subtask.
+    subtask_editing: Option<(u64, Option<usize>, String)>,
+    /// A unique ID for the subtask input field.
+    subtask_id: Id,
+    /// The last change that can be undone, taken by [`Self::take_change`].
+    change: Option<Change>,
     /// The current value of the new task input field.
     input: String,
     /// A unique ID for the new task input field.
@@ -107,14 +494,21 @@ pub struct Pomodoro {
 #[derive(Debug, Clone)]
 pub enum Message {
     // Timer messages
-    Tick(Instant),
+    Tick,
     Toggle,
     Reset,
     Finish,
+    Interrupt(Interruption),
+    /// A button clicked on the notification of the session with the given number.
+    NotificationAction(u64, Action),
 
     // Task list messages
     Input(String),
     Add,
+    FilterSelected(Filter),
+    SearchInput(String),
+    ToggleCompleted,
+    Sort(SortKey),
     Clear,
     EndDay,
 
@@ -124,32 +518,44 @@ pub enum Message {
     Delete(u64),
     Edit(u64),
     EditInput(String),
+    NotesAction(text_editor::Action),
+    ToggleNotes(u64),
+    Estimate(u64, u32),
+    PrioritySelected(u64, Priority),
     SaveEdit,
     CancelEdit,
+    NewSubtask(u64),
+    EditSubtask(u64, usize),
+    SubtaskInput(String),
+    SaveSubtask,
+    CancelSubtask,
+    ToggleSubtask(u64, usize),
+    DeleteSubtask(u64, usize),
+    RecurrenceSelected(u64, RecurrenceKind),
+    RecurrenceDayToggled(u64, Weekday),
+    RecurrenceEveryChanged(u64, u16),
+    DeleteUpcoming(usize),
 
     // Keyboard shortcut messages
     FocusInput,
+    FocusSearch,
     CompleteActive,
     Activate,
     ActiveUp,
     ActiveDown,
     EditActive,
     DeleteActive,
-}
-
-/// Represents the operational state of the timer.
-enum State {
-    Idle,
-    Ticking { expires: Instant },
-    Overtime { last_tick: Instant },
-}
-
-/// Represents the type of session currently active.
-#[derive(Debug)]
-enum Session {
-    Pomodoro,
-    Break,
-    LongBreak,
+    EstimateUp,
+    EstimateDown,
+    PriorityActive,
+    MoveUp,
+    MoveDown,
+    NewSubtaskActive,
+    SubtaskUp,
+    SubtaskDown,
+    ToggleSubtaskActive,
+    EditSubtaskActive,
+    DeleteSubtaskActive,
 }
 
 /// Used to indicate direction for moving the active task selection.
@@ -158,110 +564,267 @@ enum Direction {
     Down,
 }
 
+impl Message {
+    /// Returns the toast offering to undo the message if it deletes tasks.
+    pub fn undo_toast(&self) -> Option<&'static str> {
+        match self {
+            Message::Delete(_) | Message::DeleteActive => Some("Task deleted"),
+            Message::Clear => Some("All tasks deleted"),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the message modifies the task list, so it should be saved to disk.
+    fn updates_tasks(&self) -> bool {
+        matches!(
+            self,
+            Message::Add
+                | Message::Clear
+                | Message::Finish
+                | Message::Interrupt(_)
+                | Message::NotificationAction(..)
+                | Message::Complete(_)
+                | Message::CompleteActive
+                | Message::SaveEdit
+                | Message::Estimate(..)
+                | Message::EstimateUp
+                | Message::EstimateDown
+                | Message::Sort(_)
+                | Message::PrioritySelected(..)
+                | Message::PriorityActive
+                | Message::MoveUp
+                | Message::MoveDown
+                | Message::SaveSubtask
+                | Message::ToggleSubtask(..)
+                | Message::ToggleSubtaskActive
+                | Message::DeleteSubtask(..)
+                | Message::DeleteSubtaskActive
+                | Message::RecurrenceSelected(..)
+                | Message::RecurrenceDayToggled(..)
+                | Message::RecurrenceEveryChanged(..)
+                | Message::DeleteUpcoming(_)
+                | Message::Delete(_)
+                | Message::DeleteActive
+                | Message::EndDay
+        )
+    }
+
+    /// Returns whether the message moves the active task with the keyboard, so it should be
+    /// scrolled into view.
+    fn moves_active(&self) -> bool {
+        matches!(
+            self,
+            Message::ActiveUp | Message::ActiveDown | Message::MoveUp | Message::MoveDown
+        )
+    }
+
+    /// Returns whether the message changes the timer state, so it should be saved to survive restarts.
+    fn updates_timer(&self) -> bool {
+        matches!(
+            self,
+            Message::Toggle
+                | Message::Reset
+                | Message::Finish
+                | Message::Interrupt(_)
+                | Message::NotificationAction(..)
+                | Message::Select(_)
+                | Message::Complete(_)
+                | Message::CompleteActive
+                | Message::ActiveUp
+                | Message::ActiveDown
+                | Message::ToggleSubtask(..)
+                | Message::ToggleSubtaskActive
+        )
+    }
+}
+
 impl Pomodoro {
-    /// Initializes a new `Pomodoro` state with configured durations and themes.
+    /// Initializes a new `Pomodoro` state with the configured session cycle and themes.
     ///
-    /// It also loads any existing tasks from persistent storage.
-    pub fn new(
-        work_min: u8,
-        break_min: u8,
-        long_break_min: u8,
-        long_break_after: u8,
-        work_theme: Theme,
-        break_theme: Theme,
-    ) -> Self {
-        let work_dur = Duration::from_secs(u64::from(work_min) * 60);
-        let break_dur = Duration::from_secs(u64::from(break_min) * 60);
-        let long_break_dur = Duration::from_secs(u64::from(long_break_min) * 60);
+    /// It also loads any existing tasks and the running timer from persistent storage.
+    pub fn new(config: Config) -> Self {
+        let Config {
+            cycle,
+            auto_start,
+            break_rule,
+            overtime_policy,
+            language,
+            alerts,
+            goal,
+            auto_complete,
+            max_tasks,
+        } = config;
+        let (steps, step_themes): (Vec<Step>, Vec<Theme>) = cycle.into_iter().unzip();
+        let mut timer =
+            TimerEngine::new(SystemClock, steps, auto_start, break_rule, overtime_policy);
+        timer.set_warnings(alerts.warnings());
+        if let Ok(snapshot) = persistence::load("timer.json") {
+            timer.restore(snapshot);
+        }
         let tasks: Vec<Task> = persistence::load("tasks.json").unwrap_or_default();
+        let upcoming = persistence::load("recurring.json").unwrap_or_default();
+        let unassigned = persistence::load("unassigned.json").unwrap_or_default();
+        let today = persistence::load("today.json").map_or_else(|_| Today::new(), Today::current);
         let active = tasks.iter().find(|t| !t.done).map(|t| t.id);
 
         // The initial task receives ID 1, and subsequent IDs increment from there.
         let next_id = tasks.iter().max_by_key(|t| t.id).map_or(1, |t| t.id + 1);
 
-        Self {
-            work_dur,
-            work_count: 0,
-            break_dur,
-            long_break_dur,
-            long_break_after,
-            work_theme: work_theme.clone(),
-            break_theme,
-            remaining: work_dur,
-            overtime: Duration::ZERO,
-            last_done: work_dur,
-            state: State::Idle,
-            session: Session::Pomodoro,
-            theme: work_theme,
+        let mut pomodoro = Self {
+            step_themes,
+            theme: Theme::default(),
+            timer,
+            messages: Messages::load(),
+            language,
+            alerts,
+            reminders_sent: 0,
+            notification: None,
             tasks,
+            unassigned,
+            today,
+            goal,
+            auto_complete,
+            max_tasks,
             active,
             next_id,
+            upcoming,
             editing: None,
+            notes: text_editor::Content::new(),
+            expanded: BTreeSet::new(),
             edit_id: Id::unique(),
+            filter: Filter::All,
+            search: String::new(),
+            search_id: Id::unique(),
+            show_completed: true,
+            list_id: scrollable::Id::unique(),
+            subtask: None,
+            subtask_editing: None,
+            subtask_id: Id::unique(),
+            change: None,
             input: String::new(),
             input_id: Id::unique(),
+        };
+        pomodoro.theme = pomodoro.step_theme();
+        // Recurring tasks that came due since the last launch reappear.
+        if pomodoro.add_due_tasks() {
+            pomodoro.save_tasks();
         }
+        pomodoro
     }
 
     /// Updates the component's configuration from the settings.
-    pub fn apply_settings(&mut self, w_min: u8, b_min: u8, w_theme: Theme, b_theme: Theme) {
-        self.work_dur = Duration::from_secs(u64::from(w_min) * 60);
-        self.break_dur = Duration::from_secs(u64::from(b_min) * 60);
-        (self.work_theme, self.break_theme) = (w_theme, b_theme);
-
-        // Only reset the timer if it's not currently running.
-        if matches!(self.state, State::Idle) {
-            self.reset_duration();
-            self.theme = match self.session {
-                Session::Pomodoro => self.work_theme.clone(),
-                Session::Break | Session::LongBreak => self.break_theme.clone(),
-            };
+    pub fn apply_settings(&mut self, config: Config) {
+        let (steps, step_themes) = config.cycle.into_iter().unzip();
+        self.step_themes = step_themes;
+
+        // Only switch the theme if the timer is not currently running.
+        self.timer.set_steps(steps);
+        self.timer.set_auto_start(config.auto_start);
+        self.timer.set_break_rule(config.break_rule);
+        self.timer.set_overtime_policy(config.overtime_policy);
+        self.timer.set_warnings(config.alerts.warnings());
+        self.language = config.language;
+        self.alerts = config.alerts;
+        self.goal = config.goal;
+        self.auto_complete = config.auto_complete;
+        self.max_tasks = config.max_tasks;
+        if matches!(self.timer.state(), State::Idle) {
+            self.theme = self.step_theme();
+            persistence::save("timer.json", &self.timer.snapshot()).ok();
         }
     }
 
-    /// Returns the count of completed tasks and the total time spent on them.
-    pub fn get_completed_stats(&self) -> (Duration, usize) {
+    /// Returns the count of completed tasks, the total time and overtime spent on them and
+    /// without a task, the interruptions logged, and today's pomodoros and goal.
+    pub fn get_completed_stats(&self) -> DaySummary {
         let done_tasks: Vec<&Task> = self.tasks.iter().filter(|t| t.done).collect();
         let completed = done_tasks.len();
-        let focused = done_tasks.iter().map(|t| t.spent).sum();
-        (focused, completed)
+        let unassigned = self.unassigned.spent;
+        let focused = done_tasks
+            .iter()
+            .map(|t| t.spent)
+            .fold(unassigned, Duration::saturating_add);
+        let overtime = done_tasks
+            .iter()
+            .map(|t| t.overtime)
+            .fold(self.unassigned.overtime, Duration::saturating_add);
+        let counted_overtime = done_tasks
+            .iter()
+            .map(|t| t.counted_overtime)
+            .fold(self.unassigned.counted_overtime, Duration::saturating_add);
+        let interruptions = done_tasks
+            .iter()
+            .fold(self.unassigned.interruptions, |sum, t| {
+                sum.merged(t.interruptions)
+            });
+        let estimated_tasks = done_tasks.iter().filter(|t| t.estimate > 0);
+        let estimated = estimated_tasks.clone().map(|t| t.estimate).sum();
+        let estimate_actual = estimated_tasks.map(|t| t.pomodoros).sum();
+        let mut projects = BTreeMap::new();
+        let mut tags = BTreeMap::new();
+        let mut series: BTreeMap<u64, SeriesTime> = BTreeMap::new();
+        for task in &done_tasks {
+            let attribute = |times: &mut BTreeMap<String, Duration>, name: &String| {
+                let time = times.entry(name.clone()).or_insert(Duration::ZERO);
+                *time = time.saturating_add(task.spent);
+            };
+            if let Some(project) = &task.project {
+                attribute(&mut projects, project);
+            }
+            for tag in &task.tags {
+                attribute(&mut tags, tag);
+            }
+            // Several occurrences of a series can be completed before the same report.
+            if let Some(id) = task.series {
+                series.entry(id).or_default().merge(SeriesTime {
+                    name: task.desc.clone(),
+                    spent: task.spent,
+                    occurrences: 1,
+                });
+            }
+        }
+        let today = self.today.current();
+        DaySummary {
+            focused,
+            overtime,
+            counted_overtime,
+            unassigned,
+            completed,
+            pomodoros: today.pomodoros,
+            interruptions,
+            goal_met: self.goal.is_met(today.pomodoros, today.focused),
+            estimated,
+            estimate_actual,
+            projects,
+            tags,
+            series,
+        }
     }
 
     /// Processes messages and updates the component's state.
     pub fn update(&mut self, message: Message) -> iced::Task<Message> {
-        // Any message that modifies the task list should trigger a save to disk.
-        let task_updated = matches!(
-            message,
-            Message::Add
-                | Message::Clear
-                | Message::Finish
-                | Message::Complete(_)
-                | Message::CompleteActive
-                | Message::SaveEdit
-                | Message::Delete(_)
-                | Message::DeleteActive
-                | Message::EndDay
-        );
+        let task_updated = message.updates_tasks();
+        let timer_updated = message.updates_timer();
+        let moves_active = message.moves_active();
 
         match message {
             // Timer messages
-            Message::Tick(now) => self.handle_tick(now),
+            Message::Tick => return self.handle_tick(),
             Message::Toggle => self.toogle_timer(),
-            Message::Reset => self.reset_duration(),
+            Message::Reset => self.timer.reset(),
             Message::Finish => self.finish_timer(),
+            Message::Interrupt(kind) => self.interrupt(kind),
+            Message::NotificationAction(session, action) => self.handle_action(session, action),
 
             // Task list messages
             Message::Input(value) => self.input = value,
-            Message::Add => {
-                let desc = self.input.trim().to_string();
-                if !desc.is_empty() {
-                    self.tasks.push(Task::new(self.next_id, desc));
-                    self.next_id = self.next_id.wrapping_add(1);
-                    self.input.clear();
-                }
-            }
-            Message::Clear => self.tasks.clear(),
-            Message::EndDay => self.tasks.retain(|task| !task.done),
+            Message::Add => self.add_task(),
+            Message::FilterSelected(filter) => self.filter = filter,
+            Message::SearchInput(search) => self.search = search,
+            Message::ToggleCompleted => self.show_completed = !self.show_completed,
+            Message::Sort(key) => self.sort_tasks(key),
+            Message::Clear => self.clear_tasks(),
+            Message::EndDay => self.end_day(),
 
             // Individual task messages
             Message::Select(id) => self.select_task(id),
@@ -273,26 +836,42 @@ impl Pomodoro {
                     editing.1 = input;
                 }
             }
-            Message::SaveEdit => {
-                if let Some((id, new_text)) = self.editing.take()
-                    && !new_text.trim().is_empty()
-                    && let Some(task) = self.tasks.iter_mut().find(|t| t.id == id)
-                {
-                    task.desc = new_text;
-                }
-            }
+            Message::SaveEdit => self.save_edit(),
+            Message::NotesAction(action) => self.notes.perform(action),
+            Message::ToggleNotes(id) => self.toggle_notes(id),
             Message::CancelEdit => self.editing = None,
+            Message::Estimate(id, estimate) => self.estimate_task(id, |_| estimate),
+            Message::PrioritySelected(id, priority) => self.prioritize_task(id, |_| priority),
+            Message::RecurrenceSelected(..)
+            | Message::RecurrenceDayToggled(..)
+            | Message::RecurrenceEveryChanged(..)
+            | Message::DeleteUpcoming(_) => self.update_recurrence(&message),
+            Message::NewSubtask(_)
+            | Message::EditSubtask(..)
+            | Message::NewSubtaskActive
+            | Message::EditSubtaskActive => return self.open_subtask(&message),
+            Message::SubtaskInput(_)
+            | Message::SaveSubtask
+            | Message::CancelSubtask
+            | Message::ToggleSubtask(..)
+            | Message::DeleteSubtask(..)
+            | Message::SubtaskUp
+            | Message::SubtaskDown
+            | Message::ToggleSubtaskActive
+            | Message::DeleteSubtaskActive => self.update_subtask(&message),
 
             // Keyboard shortcut messages
             Message::FocusInput => return text_input::focus(self.input_id.clone()),
+            Message::FocusSearch => return text_input::focus(self.search_id.clone()),
             Message::CompleteActive => {
                 if let Some(active_id) = self.active {
                     self.complete_task(active_id);
                 }
             }
             Message::Activate => {
-                if let Some(task) = self.tasks.iter().find(|task| !task.done) {
-                    self.select_task(task.id);
+                let first = self.visible_tasks().find(|task| !task.done).map(|t| t.id);
+                if let Some(id) = first {
+                    self.select_task(id);
                 }
             }
             Message::ActiveUp => self.move_active(&Direction::Up),
@@ -308,10 +887,26 @@ impl Pomodoro {
                     self.delete_task(active_id);
                 }
             }
+            Message::EstimateUp => self.estimate_active(|estimate| estimate.saturating_add(1)),
+            Message::EstimateDown => self.estimate_active(|estimate| estimate.saturating_sub(1)),
+            Message::PriorityActive => {
+                if let Some(active_id) = self.active {
+                    self.prioritize_task(active_id, Priority::next);
+                }
+            }
+            Message::MoveUp => self.move_task(&Direction::Up),
+            Message::MoveDown => self.move_task(&Direction::Down),
         }
 
         if task_updated {
-            persistence::save("tasks.json", &self.tasks).ok();
+            self.save_tasks();
+        }
+        if timer_updated {
+            self.close_stale_notification();
+            persistence::save("timer.json", &self.timer.snapshot()).ok();
+        }
+        if moves_active {
+            return self.scroll_to_active();
         }
 
         iced::Task::none()
@@ -319,23 +914,40 @@ impl Pomodoro {
 
     /// Defines subscriptions for timer ticks and keyboard shortcuts.
     pub fn subscription(&self) -> Subscription<Message> {
-        let timer_sub = match self.state {
+        let timer_sub = match self.timer.state() {
             State::Idle => Subscription::none(),
-            _ => iced::time::every(Duration::from_millis(500)).map(Message::Tick),
+            _ => iced::time::every(Duration::from_millis(500)).map(|_| Message::Tick),
         };
 
-        let key_sub = iced::keyboard::on_key_press(|key, _modifiers| match key.as_ref() {
+        let key_sub = iced::keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+            // Shortcuts with Ctrl or Alt belong to the application, e.g. Ctrl+Z to undo.
+            _ if modifiers.control() || modifiers.alt() => None,
             Key::Named(Named::Space) => Some(Message::Toggle),
             Key::Character("r") => Some(Message::Reset),
             Key::Character("f") => Some(Message::Finish),
+            Key::Character("i") => Some(Message::Interrupt(Interruption::Internal)),
+            Key::Character("o") => Some(Message::Interrupt(Interruption::External)),
             Key::Character("n") => Some(Message::FocusInput),
             Key::Character("s") => Some(Message::CompleteActive),
             Key::Character("a") => Some(Message::Activate),
+            Key::Named(Named::ArrowUp) if modifiers.shift() => Some(Message::MoveUp),
+            Key::Named(Named::ArrowDown) if modifiers.shift() => Some(Message::MoveDown),
             Key::Named(Named::ArrowUp) => Some(Message::ActiveUp),
             Key::Named(Named::ArrowDown) => Some(Message::ActiveDown),
             Key::Character("e") => Some(Message::EditActive),
             Key::Character("d") => Some(Message::DeleteActive),
             Key::Character("x") => Some(Message::EndDay),
+            Key::Character("+") => Some(Message::EstimateUp),
+            Key::Character("-") => Some(Message::EstimateDown),
+            Key::Character("c") => Some(Message::NewSubtaskActive),
+            Key::Character("[") => Some(Message::SubtaskUp),
+            Key::Character("]") => Some(Message::SubtaskDown),
+            Key::Character("t") => Some(Message::ToggleSubtaskActive),
+            Key::Character("E") => Some(Message::EditSubtaskActive),
+            Key::Character("D") => Some(Message::DeleteSubtaskActive),
+            Key::Character("p") => Some(Message::PriorityActive),
+            Key::Character("h") => Some(Message::ToggleCompleted),
+            Key::Character("/") => Some(Message::FocusSearch),
             _ => None,
         });
 
@@ -344,24 +956,18 @@ impl Pomodoro {
 
     /// Builds the main view for the Pomodoro tab.
     pub fn view(&self) -> Element<'_, Message> {
-        let max_range = match self.session {
-            Session::Pomodoro => self.work_dur,
-            Session::Break => self.break_dur,
-            Session::LongBreak => self.long_break_dur,
-        };
-        let progress = progress_bar(0.0..=max_range.as_secs_f32(), self.remaining.as_secs_f32());
+        let max_range = self.timer.session_duration();
+        let remaining = self.timer.remaining();
+        let progress = progress_bar(0.0..=max_range.as_secs_f32(), remaining.as_secs_f32());
         column![progress.height(1), self.view_timer(), self.view_tasks()]
             .align_x(Center)
             .padding(10)
             .into()
     }
 
-    /// Returns the title for the Pomodoro tab, indicating the current session.
+    /// Returns the title for the Pomodoro tab, indicating the current step of the cycle.
     pub fn tab_title(&self) -> String {
-        match self.session {
-            Session::LongBreak => "Long Break".to_string(),
-            Session::Pomodoro | Session::Break => format!("{:?}", self.session),
-        }
+        self.timer.step().name.clone()
     }
 
     /// Returns the currently active theme.
@@ -369,141 +975,508 @@ impl Pomodoro {
         self.theme.clone()
     }
 
-    /// Counts down the timer. If timer is zero, then notifies and counts up the overtime.
-    fn handle_tick(&mut self, now: Instant) {
-        match &mut self.state {
-            State::Ticking { expires } => {
-                if let Some(duration) = expires.checked_duration_since(now) {
-                    self.remaining = duration;
+    /// Counts down the timer, closing the notification of a session that has just ended.
+    fn handle_tick(&mut self) -> iced::Task<Message> {
+        let clicked = self.tick_timer();
+        self.close_stale_notification();
+        clicked
+    }
+
+    /// Closes the notification waiting for a click once its session is over, which also
+    /// ends the thread waiting for the click.
+    fn close_stale_notification(&mut self) {
+        if let Some((session, id)) = self.notification
+            && session != self.timer.session_number()
+        {
+            notification::close(id);
+            self.notification = None;
+        }
+    }
+
+    /// Counts down the timer. If timer is zero, then notifies and either schedules the next
+    /// session to start automatically or counts up the overtime.
+    ///
+    /// The returned task resolves to the message of the button clicked on the notification.
+    fn tick_timer(&mut self) -> iced::Task<Message> {
+        match self.timer.tick() {
+            Some(Event::Expired(session)) => {
+                let upcoming = self.timer.next_step().session;
+                self.reminders_sent = 0;
+                let auto_starts = self.timer.auto_starts_after(session);
+                if auto_starts {
+                    self.finish_timer();
+                    self.timer.schedule_start();
+                    self.save_tasks();
+                    persistence::save("timer.json", &self.timer.snapshot()).ok();
+                }
+
+                // Announce the upcoming session with a message from its pool.
+                let behavior = if upcoming == Session::LongBreak {
+                    &self.alerts.long_break
                 } else {
-                    match self.session {
-                        Session::Break | Session::LongBreak => {
-                            let index = rand::rng().random_range(0..WORK_SUMMARIES.len());
-                            let _ = Notification::new()
-                                .sound_name("alarm-clock-elapsed")
-                                .summary(WORK_SUMMARIES[index])
-                                .show();
-                        }
-                        Session::Pomodoro => {
-                            let index = rand::rng().random_range(0..BREAK_SUMMARIES.len());
-                            let _ = Notification::new()
-                                .sound_name("alarm-clock-elapsed")
-                                .summary(BREAK_SUMMARIES[index])
-                                .show();
-                        }
+                    &self.alerts.session_end
+                };
+                if !behavior.enabled {
+                    return iced::Task::none();
+                }
+                let mut actions = match upcoming {
+                    Session::Pomodoro => vec![Action::StartPomodoro],
+                    Session::Break | Session::LongBreak => {
+                        vec![Action::StartBreak, Action::SkipBreak]
                     }
-                    self.remaining = Duration::ZERO;
-                    self.state = State::Overtime { last_tick: now };
+                };
+                // A session that was finished automatically can no longer be extended.
+                if !auto_starts {
+                    actions.push(Action::Extend);
                 }
+                let mut notification =
+                    behavior.notification(&self.messages.pick(&self.language, upcoming));
+                let session = self.timer.session_number();
+                let (id, clicked) = notification::show(&mut notification, &actions);
+                if let Some((_, previous)) = self.notification.take() {
+                    notification::close(previous);
+                }
+                self.notification = id.map(|id| (session, id));
+                return clicked.map(move |action| Message::NotificationAction(session, action));
+            }
+            // A session waiting for its auto-start has just started.
+            Some(Event::Started) => {
+                persistence::save("timer.json", &self.timer.snapshot()).ok();
             }
-            State::Overtime { last_tick } => {
-                self.overtime = self.overtime.saturating_add(now - *last_tick);
-                *last_tick = now;
+            // The session ran too long in overtime, so it is finished automatically.
+            Some(Event::OvertimeLimit) => {
+                self.finish_timer();
+                self.save_tasks();
+                persistence::save("timer.json", &self.timer.snapshot()).ok();
             }
-            State::Idle => {}
+            // Give a heads-up that the session is about to run out.
+            Some(Event::Warning(left)) => {
+                let secs = left.as_secs();
+                let summary = format!(
+                    "{}:{:0>2} left in {}",
+                    secs / 60,
+                    secs % 60,
+                    self.timer.step().name
+                );
+                let _ = self.alerts.warning.notification(&summary).show();
+            }
+            // Remind that the session is still running in overtime.
+            Some(Event::Overtime) if self.is_expired() => {
+                let due = self.alerts.reminders_due(self.timer.overtime());
+                if due > self.reminders_sent {
+                    self.reminders_sent = due;
+                    let minutes = self.timer.overtime().as_secs() / 60;
+                    let summary = format!("{} is {minutes} minutes over", self.timer.step().name);
+                    let _ = self.alerts.overtime.notification(&summary).show();
+                }
+            }
+            Some(Event::Overtime | Event::Finished(_)) | None => {}
+        }
+        iced::Task::none()
+    }
+
+    /// Logs an interruption of the running session and the active task.
+    fn interrupt(&mut self, kind: Interruption) {
+        self.timer.interrupt(kind);
+        let active = self.active;
+        match self.tasks.iter_mut().find(|task| Some(task.id) == active) {
+            Some(task) => task.interruptions.add(kind),
+            None => self.unassigned.interruptions.add(kind),
         }
     }
 
-    /// Starts or stops the timer.
+    /// Carries out the button clicked on a notification, unless its session is already over.
+    fn handle_action(&mut self, session: u64, action: Action) {
+        if session != self.timer.session_number() {
+            return;
+        }
+        match action {
+            Action::StartBreak | Action::StartPomodoro => self.start_next(),
+            Action::SkipBreak => self.skip_break(),
+            Action::Extend => self.timer.extend(EXTENSION),
+        }
+    }
+
+    /// Finishes the expired session and starts the next one right away.
+    fn start_next(&mut self) {
+        if self.is_expired() {
+            self.finish_timer();
+        }
+        if let State::Idle | State::Waiting { .. } = self.timer.state() {
+            self.toogle_timer();
+        }
+    }
+
+    /// Finishes the expired work session, skips the break after it and starts working again.
+    fn skip_break(&mut self) {
+        if self.timer.session() == Session::Pomodoro && self.is_expired() {
+            self.finish_timer();
+        }
+        if self.timer.session() != Session::Pomodoro {
+            self.finish_timer();
+            self.toogle_timer();
+        }
+    }
+
+    /// Returns whether the current session has run out and is counting up the overtime.
+    fn is_expired(&self) -> bool {
+        matches!(self.timer.state(), State::Overtime { .. }) && !self.timer.step().counts_up()
+    }
+
+    /// Starts or stops the timer, switching to the theme of the started session.
     fn toogle_timer(&mut self) {
-        let expires = Instant::now() + self.remaining;
-        self.state = match self.state {
-            State::Idle => State::Ticking { expires },
-            _ => State::Idle,
+        if let Some(Event::Started) = self.timer.toggle() {
+            self.theme = self.step_theme();
         }
     }
 
-    /// Logs the work time spent on the active task, then switches to the next session type.
+    /// Logs the work time spent on the active task, or as unassigned without one, and records
+    /// the session in the log, then switches to the next session type.
     fn finish_timer(&mut self) {
-        if let (Session::Pomodoro, Some(id)) = (&self.session, self.active) {
-            let time_spent = self.get_time_spent();
-            if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
-                task.spent = task.spent.saturating_add(time_spent);
+        if let Event::Finished(summary) = self.timer.finish() {
+            let mut task_info = None;
+            if summary.session == Session::Pomodoro {
+                // Skipping a session that was never started does not finish a pomodoro.
+                let started = summary.started_at.is_some();
+                self.today.add(summary.spent.focus, started);
+                let active = self.active;
+                match self.tasks.iter_mut().find(|task| Some(task.id) == active) {
+                    Some(task) => {
+                        if started {
+                            task.pomodoros = task.pomodoros.saturating_add(1);
+                        }
+                        task.spent = task.spent.saturating_add(summary.spent.focus);
+                        task.overtime = task.overtime.saturating_add(summary.spent.overtime);
+                        task.counted_overtime =
+                            task.counted_overtime.saturating_add(summary.spent.counted);
+                        task_info = Some((task.id, task.desc.clone()));
+                    }
+                    None => self.unassigned.add(summary.spent),
+                }
             }
-            self.work_count += 1;
+            if let Some(record) = SessionRecord::new(&summary, task_info) {
+                session_log::append(&record);
+            }
+            self.theme = self.step_theme();
         }
+    }
+
+    /// Returns the change made by the last message if it can be undone.
+    pub fn take_change(&mut self) -> Option<Change> {
+        self.change.take()
+    }
 
-        self.session = match self.session {
-            Session::Pomodoro if self.work_count >= self.long_break_after => {
-                self.work_count = 0;
-                self.theme = self.break_theme.clone();
-                Session::LongBreak
+    /// Undoes a change and returns the change that redoes it, `None` if the tasks it touched
+    /// are gone.
+    pub fn undo(&mut self, change: Change) -> Option<Change> {
+        let redo = match change.0 {
+            ChangeKind::Insert(tasks) => {
+                let ids = tasks.iter().map(|(_, task)| task.id).collect();
+                for (index, task) in tasks {
+                    self.tasks.insert(index.min(self.tasks.len()), task);
+                }
+                Some(ChangeKind::Remove(ids))
+            }
+            ChangeKind::Remove(ids) => {
+                let removed = self.remove_tasks(|task| ids.contains(&task.id));
+                (!removed.is_empty()).then_some(ChangeKind::Insert(removed))
             }
-            Session::Pomodoro => {
-                self.theme = self.break_theme.clone();
-                Session::Break
+            ChangeKind::Edit(id, details) => {
+                let task = self.tasks.iter_mut().find(|task| task.id == id);
+                task.map(|task| ChangeKind::Edit(id, task.replace_details(details)))
             }
-            Session::Break | Session::LongBreak => {
-                self.theme = self.work_theme.clone();
-                Session::Pomodoro
+            ChangeKind::Complete {
+                id,
+                done,
+                completed_on,
+                active,
+            } => {
+                let current = self.active;
+                let task = self.tasks.iter_mut().find(|task| task.id == id);
+                task.map(|task| {
+                    let redo = ChangeKind::Complete {
+                        id,
+                        done: std::mem::replace(&mut task.done, done),
+                        completed_on: std::mem::replace(&mut task.completed_on, completed_on),
+                        active: current,
+                    };
+                    self.active = active;
+                    redo
+                })
             }
         };
-        self.reset_duration();
+        self.editing = None;
+        self.subtask_editing = None;
+        self.save_tasks();
+        redo.map(Change)
     }
 
-    /// Resets the timer to the current session's full duration.
-    fn reset_duration(&mut self) {
-        self.remaining = match self.session {
-            Session::Pomodoro => self.work_dur,
-            Session::Break => self.break_dur,
-            Session::LongBreak => self.long_break_dur,
+    /// Removes the matching tasks and returns them with their positions, in ascending order.
+    fn remove_tasks(&mut self, remove: impl Fn(&Task) -> bool) -> Vec<(usize, Task)> {
+        let mut removed = Vec::new();
+        let mut index = 0;
+        self.tasks.retain(|task| {
+            let keep = !remove(task);
+            if !keep {
+                removed.push((index, task.clone()));
+            }
+            index += 1;
+            keep
+        });
+        if self
+            .active
+            .is_some_and(|active| removed.iter().any(|(_, task)| task.id == active))
+        {
+            self.active = self
+                .tasks
+                .iter()
+                .find(|task| !task.done)
+                .map(|task| task.id);
+        }
+        removed
+    }
+
+    /// Deletes all tasks.
+    fn clear_tasks(&mut self) {
+        let removed = self.remove_tasks(|_| true);
+        if !removed.is_empty() {
+            self.change = Some(Change(ChangeKind::Insert(removed)));
+        }
+    }
+
+    /// Returns the completed tasks, which are removed from the list when the day ends.
+    pub fn completed_tasks(&self) -> Vec<Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.done)
+            .cloned()
+            .collect()
+    }
+
+    /// Puts an archived task back on the list under a new ID. Its logged time and
+    /// interruptions start over, as they already went into a report.
+    pub fn restore_task(&mut self, task: Task) {
+        let restored = Task {
+            id: self.next_id,
+            // A restored occurrence stays linked to its series, but does not start a new one.
+            recurrence: None,
+            ..task.reset()
         };
-        self.last_done = self.remaining;
-        self.overtime = Duration::ZERO;
-        self.state = State::Idle;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.tasks.push(restored);
+        self.save_tasks();
     }
 
-    /// Calculates the total time spent in the current pomodoro segment.
-    fn get_time_spent(&self) -> Duration {
-        if self.remaining.is_zero() {
-            self.last_done + self.overtime
-        } else {
-            self.last_done - self.remaining
+    /// Removes the completed tasks and the unassigned time, once they went into the report.
+    /// Completed recurring tasks wait for their next occurrence, and the ones already due
+    /// reappear.
+    fn end_day(&mut self) {
+        let today = Local::now().date_naive();
+        for task in self.tasks.iter().filter(|task| task.done) {
+            if let Some(recurrence) = &task.recurrence {
+                // The next occurrence follows the day the task was done, not the day that
+                // ends, so ending the day late skips no occurrence.
+                let done = task.completed_on.unwrap_or(today);
+                self.upcoming.push(Upcoming {
+                    due: recurrence.next_due(done),
+                    task: task.next_occurrence(),
+                });
+            }
         }
+        self.tasks.retain(|task| !task.done);
+        self.unassigned = Unassigned::default();
+        self.add_due_tasks();
+    }
+
+    /// Adds the recurring tasks that are due by today to the list, returning whether any was.
+    fn add_due_tasks(&mut self) -> bool {
+        let today = Local::now().date_naive();
+        let (due, waiting): (Vec<Upcoming>, Vec<Upcoming>) = std::mem::take(&mut self.upcoming)
+            .into_iter()
+            .partition(|upcoming| upcoming.due <= today);
+        self.upcoming = waiting;
+        let added = !due.is_empty();
+        for upcoming in due {
+            self.tasks.push(Task {
+                id: self.next_id,
+                ..upcoming.task
+            });
+            self.next_id = self.next_id.wrapping_add(1);
+        }
+        if self.active.is_none() {
+            self.active = self
+                .tasks
+                .iter()
+                .find(|task| !task.done)
+                .map(|task| task.id);
+        }
+        added
+    }
+
+    /// Shows the notes of the task below it, or hides them if they were shown.
+    fn toggle_notes(&mut self, id: u64) {
+        if !self.expanded.remove(&id) {
+            self.expanded.insert(id);
+        }
+    }
+
+    /// Processes messages that change the schedule of recurring tasks.
+    fn update_recurrence(&mut self, message: &Message) {
+        if let Message::DeleteUpcoming(index) = *message {
+            if index < self.upcoming.len() {
+                self.upcoming.remove(index);
+            }
+            return;
+        }
+        let (Message::RecurrenceSelected(id, _)
+        | Message::RecurrenceDayToggled(id, _)
+        | Message::RecurrenceEveryChanged(id, _)) = *message
+        else {
+            return;
+        };
+        let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) else {
+            return;
+        };
+        match (message, &mut task.recurrence) {
+            (&Message::RecurrenceSelected(_, kind), recurrence) => {
+                *recurrence = kind.recurrence(Local::now().date_naive());
+            }
+            (&Message::RecurrenceDayToggled(_, day), Some(Recurrence::Days(days))) => {
+                // The last day stays, the task recurs on at least one day of the week.
+                if let Some(index) = days.iter().position(|&d| d == day) {
+                    if days.len() > 1 {
+                        days.remove(index);
+                    }
+                } else {
+                    days.push(day);
+                    days.sort_by_key(Weekday::num_days_from_monday);
+                }
+            }
+            (&Message::RecurrenceEveryChanged(_, value), Some(Recurrence::Every(days))) => {
+                *days = value;
+            }
+            _ => {}
+        }
+        // The first time a task recurs, it starts a new series.
+        if task.recurrence.is_some() && task.series.is_none() {
+            task.series = Some(rand::random());
+        }
+    }
+
+    /// Saves the task list and the time logged without a task.
+    fn save_tasks(&self) {
+        persistence::save("tasks.json", &self.tasks).ok();
+        persistence::save("recurring.json", &self.upcoming).ok();
+        persistence::save("unassigned.json", &self.unassigned).ok();
+        persistence::save("today.json", &self.today).ok();
+    }
+
+    /// Returns the theme belonging to the current step of the cycle.
+    fn step_theme(&self) -> Theme {
+        // The themes already belong to the new cycle, the started session keeps its own.
+        if self.timer.has_pending_steps() {
+            return self.theme.clone();
+        }
+        self.step_themes
+            .get(self.timer.step_index())
+            .cloned()
+            .unwrap_or_default()
     }
 
     /// Selects or deselects a task as active.
     fn select_task(&mut self, id: u64) {
         if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
             task.done = false;
+            task.completed_on = None;
         }
         self.active = (self.active != Some(id)).then_some(id);
-        self.state = State::Idle;
+        self.timer.pause();
     }
 
     /// Moves the active task selection up or down from the list of incomplete tasks.
     fn move_active(&mut self, direction: &Direction) {
-        let active_tasks: Vec<&Task> = self.tasks.iter().filter(|task| !task.done).collect();
+        let active_tasks: Vec<&Task> = self.visible_tasks().filter(|task| !task.done).collect();
         let Some(current_active_id) = self.active else {
             return;
         };
+        // The filter may hide every open task.
+        if active_tasks.is_empty() {
+            return;
+        }
 
         let current_index = active_tasks
             .iter()
-            .position(|task| task.id == current_active_id)
-            .unwrap_or(0);
+            .position(|task| task.id == current_active_id);
 
-        // Activate the next or previous task, wrapping around at the ends.
-        let new_index = match direction {
-            Direction::Up => (current_index + active_tasks.len() - 1) % active_tasks.len(),
-            Direction::Down => (current_index + 1) % active_tasks.len(),
+        // Activate the next or previous task, wrapping around at the ends. An active task
+        // hidden by the search or the filter gives way to the first or last shown one.
+        let len = active_tasks.len();
+        let new_index = match (direction, current_index) {
+            (Direction::Up, Some(index)) => (index + len - 1) % len,
+            (Direction::Down, Some(index)) => (index + 1) % len,
+            (Direction::Up, None) => len - 1,
+            (Direction::Down, None) => 0,
         };
 
         self.active = active_tasks.get(new_index).map(|task| task.id);
-        self.state = State::Idle;
+        self.timer.pause();
+    }
+
+    /// Moves the active task above or below its neighbour in the shown list, keeping the
+    /// new order in the saved tasks.
+    fn move_task(&mut self, direction: &Direction) {
+        let Some(active_id) = self.active else {
+            return;
+        };
+        let shown: Vec<u64> = self.visible_tasks().map(|task| task.id).collect();
+        let Some(position) = shown.iter().position(|&id| id == active_id) else {
+            return;
+        };
+        let neighbour = match direction {
+            Direction::Up => position.checked_sub(1),
+            Direction::Down => Some(position + 1),
+        };
+        let Some(&other_id) = neighbour.and_then(|neighbour| shown.get(neighbour)) else {
+            return;
+        };
+        let index = |id| self.tasks.iter().position(|task| task.id == id);
+        if let (Some(active), Some(other)) = (index(active_id), index(other_id)) {
+            self.tasks.swap(active, other);
+        }
+    }
+
+    /// Sorts the task list, keeping the order of tasks that compare equal.
+    fn sort_tasks(&mut self, key: SortKey) {
+        match key {
+            SortKey::Priority => self
+                .tasks
+                .sort_by_key(|task| std::cmp::Reverse(task.priority)),
+            SortKey::Spent => self.tasks.sort_by_key(|task| std::cmp::Reverse(task.spent)),
+            SortKey::Created => self.tasks.sort_by_key(|task| task.id),
+        }
     }
 
     /// Toggles the completion status of a task and logs the time spent.
     fn complete_task(&mut self, id: u64) {
-        let time_spent = self.get_time_spent();
+        let session = self.timer.session();
+        let time_spent = self.timer.split();
+        if session == Session::Pomodoro {
+            self.today.add(time_spent.focus, false);
+        }
         if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            self.change = Some(Change(ChangeKind::Complete {
+                id,
+                done: task.done,
+                completed_on: task.completed_on,
+                active: self.active,
+            }));
             task.done = !task.done;
-            task.spent = task.spent.saturating_add(time_spent);
+            task.completed_on = task.done.then(|| Local::now().date_naive());
+            task.spent = task.spent.saturating_add(time_spent.focus);
+            task.overtime = task.overtime.saturating_add(time_spent.overtime);
+            task.counted_overtime = task.counted_overtime.saturating_add(time_spent.counted);
         }
 
-        self.last_done = self.remaining;
-        self.overtime = Duration::ZERO;
-        self.state = State::Idle;
         self.active = self
             .tasks
             .iter()
@@ -511,16 +1484,265 @@ impl Pomodoro {
             .map(|task| task.id);
     }
 
+    /// Adds a new task from the input field.
+    fn add_task(&mut self) {
+        if self.is_full() {
+            return;
+        }
+        let task = Task::new(self.next_id, &self.input);
+        if !task.desc.is_empty() {
+            self.change = Some(Change(ChangeKind::Remove(vec![task.id])));
+            self.tasks.push(task);
+            self.next_id = self.next_id.wrapping_add(1);
+            self.input.clear();
+        }
+    }
+
+    /// Saves the description, project, tags, and notes of the task being edited, unless the
+    /// description was left empty.
+    fn save_edit(&mut self) {
+        let Some((id, new_text)) = self.editing.take() else {
+            return;
+        };
+        let (desc, project, tags) = parse_labels(&new_text);
+        if !desc.is_empty()
+            && let Some(task) = self.tasks.iter_mut().find(|t| t.id == id)
+        {
+            let details = Details {
+                desc,
+                project,
+                tags,
+                notes: self.notes.text().trim_end().to_string(),
+            };
+            let before = task.replace_details(details);
+            self.change = Some(Change(ChangeKind::Edit(id, before)));
+        }
+    }
+
+    /// Returns whether the task list reached its size limit.
+    fn is_full(&self) -> bool {
+        self.max_tasks.is_some_and(|max| self.tasks.len() >= max)
+    }
+
+    /// Returns the tasks shown with the current filter, search text, and completed toggle.
+    fn visible_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(|task| {
+            task.matches(&self.filter)
+                && task.matches_search(&self.search)
+                && (self.show_completed || !task.done)
+        })
+    }
+
+    /// Scrolls the task list so the active task is in view, relative to its position among
+    /// the shown tasks.
+    fn scroll_to_active(&self) -> iced::Task<Message> {
+        let shown: Vec<u64> = self.visible_tasks().map(|task| task.id).collect();
+        let Some(index) = self
+            .active
+            .and_then(|active| shown.iter().position(|&id| id == active))
+        else {
+            return iced::Task::none();
+        };
+        let to_f32 = |value: usize| f32::from(u16::try_from(value).unwrap_or(u16::MAX));
+        let y = to_f32(index) / to_f32(shown.len().saturating_sub(1)).max(1.0);
+        scrollable::snap_to(
+            self.list_id.clone(),
+            scrollable::RelativeOffset { x: 0.0, y },
+        )
+    }
+
+    /// Returns the filters of every project and tag used in the task list.
+    fn filters(&self) -> Vec<Filter> {
+        let projects: BTreeSet<&String> = self.tasks.iter().flat_map(|t| &t.project).collect();
+        let tags: BTreeSet<&String> = self.tasks.iter().flat_map(|t| &t.tags).collect();
+        std::iter::once(Filter::All)
+            .chain(projects.into_iter().cloned().map(Filter::Project))
+            .chain(tags.into_iter().cloned().map(Filter::Tag))
+            .collect()
+    }
+
     /// Puts a task into editing mode.
     fn edit_task(&mut self, id: u64) {
         if let Some(task) = self.tasks.iter().find(|task| task.id == id) {
-            self.editing = Some((task.id, task.desc.clone()));
+            let labels = task.format_labels();
+            let text = if labels.is_empty() {
+                task.desc.clone()
+            } else {
+                format!("{} {labels}", task.desc)
+            };
+            self.editing = Some((task.id, text));
+            self.notes = text_editor::Content::with_text(&task.notes);
+        }
+    }
+
+    /// Changes the estimated pomodoros of a task.
+    fn estimate_task(&mut self, id: u64, change: impl FnOnce(u32) -> u32) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            task.estimate = change(task.estimate);
+        }
+    }
+
+    /// Changes the priority of a task.
+    fn prioritize_task(&mut self, id: u64, change: impl FnOnce(Priority) -> Priority) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            task.priority = change(task.priority);
+        }
+    }
+
+    /// Changes the estimated pomodoros of the active task, if any.
+    fn estimate_active(&mut self, change: impl FnOnce(u32) -> u32) {
+        if let Some(active_id) = self.active {
+            self.estimate_task(active_id, change);
+        }
+    }
+
+    /// Opens the input field for a new subtask or for editing an existing one.
+    fn open_subtask(&mut self, message: &Message) -> iced::Task<Message> {
+        let target = match *message {
+            Message::NewSubtask(id) => Some((id, None)),
+            Message::EditSubtask(id, index) => Some((id, Some(index))),
+            Message::NewSubtaskActive => self.active.map(|id| (id, None)),
+            Message::EditSubtaskActive => {
+                self.selected_subtask().map(|(id, index)| (id, Some(index)))
+            }
+            _ => None,
+        };
+        let Some((id, index)) = target else {
+            return iced::Task::none();
+        };
+        let Some(task) = self.tasks.iter().find(|task| task.id == id) else {
+            return iced::Task::none();
+        };
+        let desc = match index {
+            Some(index) => match task.subtasks.get(index) {
+                Some(subtask) => subtask.desc.clone(),
+                None => return iced::Task::none(),
+            },
+            None => String::new(),
+        };
+        self.subtask_editing = Some((id, index, desc));
+        text_input::focus(self.subtask_id.clone())
+    }
+
+    /// Processes messages that change the subtasks.
+    fn update_subtask(&mut self, message: &Message) {
+        match *message {
+            Message::SubtaskInput(ref input) => {
+                if let Some(editing) = &mut self.subtask_editing {
+                    editing.2.clone_from(input);
+                }
+            }
+            Message::SaveSubtask => self.save_subtask(),
+            Message::CancelSubtask => self.subtask_editing = None,
+            Message::ToggleSubtask(id, index) => self.toggle_subtask(id, index),
+            Message::DeleteSubtask(id, index) => self.delete_subtask(id, index),
+            Message::SubtaskUp => self.move_subtask(&Direction::Up),
+            Message::SubtaskDown => self.move_subtask(&Direction::Down),
+            Message::ToggleSubtaskActive => {
+                if let Some((id, index)) = self.selected_subtask() {
+                    self.toggle_subtask(id, index);
+                }
+            }
+            Message::DeleteSubtaskActive => {
+                if let Some((id, index)) = self.selected_subtask() {
+                    self.delete_subtask(id, index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the selected subtask of the active task, or its first unticked subtask if none
+    /// was selected.
+    fn selected_subtask(&self) -> Option<(u64, usize)> {
+        let task = self
+            .tasks
+            .iter()
+            .find(|task| Some(task.id) == self.active)?;
+        match self.subtask {
+            Some((id, index)) if id == task.id && index < task.subtasks.len() => Some((id, index)),
+            _ => task
+                .subtasks
+                .iter()
+                .position(|subtask| !subtask.done)
+                .map(|index| (task.id, index)),
+        }
+    }
+
+    /// Saves the subtask being added or edited. After adding one, the input stays open for the
+    /// next subtask until it is submitted empty.
+    fn save_subtask(&mut self) {
+        let Some((id, index, desc)) = self.subtask_editing.take() else {
+            return;
+        };
+        let desc = desc.trim().to_string();
+        let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) else {
+            return;
+        };
+        if desc.is_empty() {
+            return;
+        }
+        if let Some(index) = index {
+            if let Some(subtask) = task.subtasks.get_mut(index) {
+                subtask.desc = desc;
+            }
+        } else {
+            task.subtasks.push(Subtask { desc, done: false });
+            self.subtask_editing = Some((id, None, String::new()));
+        }
+    }
+
+    /// Ticks or unticks a subtask, completing its task once all of them are ticked if
+    /// auto-completion is turned on.
+    fn toggle_subtask(&mut self, id: u64, index: usize) {
+        let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) else {
+            return;
+        };
+        if let Some(subtask) = task.subtasks.get_mut(index) {
+            subtask.done = !subtask.done;
+        }
+        let all_done = task.subtasks.iter().all(|subtask| subtask.done);
+        if self.auto_complete && all_done && !task.done {
+            self.complete_task(id);
+        }
+    }
+
+    /// Deletes a subtask from its task.
+    fn delete_subtask(&mut self, id: u64, index: usize) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id)
+            && index < task.subtasks.len()
+        {
+            task.subtasks.remove(index);
+            self.subtask = None;
+            self.subtask_editing = None;
+        }
+    }
+
+    /// Moves the subtask selection of the active task up or down, wrapping around at the ends.
+    fn move_subtask(&mut self, direction: &Direction) {
+        let Some(task) = self.tasks.iter().find(|task| Some(task.id) == self.active) else {
+            return;
+        };
+        let len = task.subtasks.len();
+        if len == 0 {
+            return;
         }
+        let current = self.selected_subtask().map(|(_, index)| index);
+        let index = match (direction, current) {
+            (Direction::Up, Some(index)) => (index + len - 1) % len,
+            (Direction::Down, Some(index)) => (index + 1) % len,
+            (Direction::Up, None) => len - 1,
+            (Direction::Down, None) => 0,
+        };
+        self.subtask = Some((task.id, index));
     }
 
     /// Deletes a task from the list.
     fn delete_task(&mut self, id: u64) {
-        self.tasks.retain(|task| task.id != id);
+        let removed = self.remove_tasks(|task| task.id == id);
+        if !removed.is_empty() {
+            self.change = Some(Change(ChangeKind::Insert(removed)));
+        }
         if let Some(task) = self.tasks.iter().find(|task| !task.done) {
             self.active = Some(task.id);
         }
@@ -528,99 +1750,249 @@ impl Pomodoro {
 
     /// View section for the timer display and controls.
     fn view_timer(&self) -> Element<'_, Message> {
-        let remaining_secs = self.remaining.as_secs();
-        let duration_text = format!("{}:{:0>2}", remaining_secs / 60, remaining_secs % 60);
+        // A counting up step shows the time worked and the break it earned so far.
+        let earned_break = self.timer.earned_break();
+        let shown = if earned_break.is_some() {
+            self.timer.elapsed()
+        } else {
+            self.timer.remaining()
+        };
+        let shown_secs = shown.as_secs();
+        let duration_text = format!("{}:{:0>2}", shown_secs / 60, shown_secs % 60);
 
-        let overtime_secs = self.overtime.as_secs();
-        let overtime_widget = if let State::Overtime { .. } = self.state {
+        let overtime_secs = self.timer.overtime().as_secs();
+        let overtime_widget = if let Some(earned) = earned_break {
+            let earned_secs = earned.as_secs();
+            column![
+                text!(
+                    "Earned break: {}:{:0>2}",
+                    earned_secs / 60,
+                    earned_secs % 60
+                )
+                .size(16)
+            ]
+        } else if let State::Overtime { .. } = self.timer.state() {
             column![text!("+{}:{:0>2}", overtime_secs / 60, overtime_secs % 60).size(16)]
         } else {
             column![]
         };
 
-        let is_idle = matches!(self.state, State::Idle);
+        // Show the countdown of a session that is about to start automatically.
+        let grace_widget = if let Some(left) = self.timer.grace_left() {
+            let left_secs = left.as_secs_f32().ceil();
+            column![text!("Starting in {left_secs}s").size(16)]
+        } else {
+            column![]
+        };
+
+        let is_idle = matches!(self.timer.state(), State::Idle | State::Waiting { .. });
         let toggle_text = if is_idle { "Start" } else { "Pause" };
 
+        // Change the timer's colour in the last seconds of a running session.
+        let highlight = Duration::from_secs(u64::from(self.alerts.highlight_sec));
+        let ending = matches!(self.timer.state(), State::Ticking { .. }) && shown <= highlight;
+        let duration_text = text(duration_text).size(40);
+
+        // Show the progress toward the long break, e.g. ●●○○ after two pomodoros.
+        let (done, total) = self.timer.cycle_progress();
+        let cycle_widget = if total > 1 {
+            let dots = format!("{}{}", "●".repeat(done), "○".repeat(total - done));
+            column![text(dots).shaping(text::Shaping::Advanced)]
+        } else {
+            column![]
+        };
+
+        let interruptions = self.timer.interruptions();
         column![
-            text(duration_text).size(40),
+            if ending {
+                duration_text.style(text::danger)
+            } else {
+                duration_text
+            },
+            cycle_widget,
+            self.view_goal(),
             overtime_widget,
+            grace_widget,
             row![
                 button(toggle_text).on_press(Message::Toggle),
                 button("Reset").on_press(Message::Reset),
                 button("Finish").on_press(Message::Finish),
             ]
             .spacing(20),
+            row![
+                button(text!("Internal ({})", interruptions.internal))
+                    .style(button::secondary)
+                    .on_press(Message::Interrupt(Interruption::Internal)),
+                button(text!("External ({})", interruptions.external))
+                    .style(button::secondary)
+                    .on_press(Message::Interrupt(Interruption::External)),
+            ]
+            .spacing(20),
         ]
         .spacing(10)
         .align_x(Center)
         .into()
     }
 
-    /// View section for the task list and input form.
-    fn view_tasks(&self) -> Element<'_, Message> {
-        let tasks_list = self.tasks.iter().map(|task| {
-            let view: Element<_> = match self.editing.as_ref() {
-                // Render the editing view for the selected task.
-                Some((id, desc)) if *id == task.id => row![
-                    text_input("Edit task...", desc)
-                        .id(self.edit_id.clone())
-                        .on_input(Message::EditInput)
-                        .on_submit(Message::SaveEdit),
+    /// View section for the progress toward the daily goal, empty if no goal is set.
+    fn view_goal(&self) -> Element<'_, Message> {
+        if !self.goal.is_set() {
+            return column![].into();
+        }
+        let today = self.today.current();
+        let mut targets = Vec::new();
+        if self.goal.pomodoros > 0 {
+            targets.push(format!("{}/{} 🍅", today.pomodoros, self.goal.pomodoros));
+        }
+        if !self.goal.focused.is_zero() {
+            let hours_minutes = |duration: Duration| {
+                let minutes = duration.as_secs() / 60;
+                format!("{}:{:0>2}", minutes / 60, minutes % 60)
+            };
+            targets.push(format!(
+                "{}/{} focused",
+                hours_minutes(today.focused),
+                hours_minutes(self.goal.focused)
+            ));
+        }
+        let goal_met = self.goal.is_met(today.pomodoros, today.focused);
+        let mark = if goal_met { " ✓" } else { "" };
+
+        text!("Daily goal: {}{mark}", targets.join(", "))
+            .shaping(text::Shaping::Advanced)
+            .style(if goal_met {
+                text::success
+            } else {
+                text::default
+            })
+            .into()
+    }
+
+    /// View section for a single task, either as a row or in editing mode.
+    fn view_task<'a>(&'a self, task: &'a Task) -> Element<'a, Message> {
+        let task_id = task.id;
+        match self.editing.as_ref() {
+            // Render the editing view for the selected task.
+            Some((id, desc)) if *id == task.id => column![
+                text_input("Edit task...", desc)
+                    .id(self.edit_id.clone())
+                    .on_input(Message::EditInput)
+                    .on_submit(Message::SaveEdit),
+                text_editor(&self.notes)
+                    .placeholder("Notes...")
+                    .on_action(Message::NotesAction)
+                    .height(100),
+                row![
+                    number_input(&task.estimate, 0..=99, move |estimate| {
+                        Message::Estimate(task_id, estimate)
+                    })
+                    .width(60),
+                    pick_list(Priority::ALL, Some(task.priority), move |priority| {
+                        Message::PrioritySelected(task_id, priority)
+                    }),
                     button("Save").on_press(Message::SaveEdit),
                     button("Cancel").on_press(Message::CancelEdit),
                 ]
                 .spacing(10)
-                .align_y(Center)
-                .into(),
-                // Render the normal view for all other tasks.
-                _ => {
-                    let done_icon = if task.done {
-                        text("⊗").shaping(text::Shaping::Advanced)
-                    } else {
-                        text("⊙").shaping(text::Shaping::Advanced)
-                    };
-                    let task_style = match (self.active == Some(task.id), task.done) {
-                        (true, _) => button::primary,
-                        (false, true) => button::success,
-                        _ => button::secondary,
-                    };
+                .align_y(Center),
+                Self::view_recurrence(task),
+            ]
+            .spacing(10)
+            .into(),
+            // Render the normal view for all other tasks.
+            _ => {
+                let done_icon = if task.done {
+                    text("⊗").shaping(text::Shaping::Advanced)
+                } else {
+                    text("⊙").shaping(text::Shaping::Advanced)
+                };
+                let task_style = match (self.active == Some(task.id), task.done) {
+                    (true, _) => button::primary,
+                    (false, true) => button::success,
+                    _ => button::secondary,
+                };
 
-                    row![
-                        button(done_icon)
-                            .on_press(Message::Complete(task.id))
-                            .style(task_style),
-                        button(text(&task.desc))
-                            .style(task_style)
-                            .width(Length::Fill)
-                            .on_press(Message::Select(task.id)),
-                        text(format_duration(task.spent)),
-                        button(text("⋯").shaping(text::Shaping::Advanced))
-                            .on_press(Message::Edit(task.id)),
-                        button(text("×").shaping(text::Shaping::Advanced))
-                            .style(button::danger)
-                            .on_press(Message::Delete(task.id)),
-                    ]
+                let priority_style = match task.priority {
+                    Priority::High => text::danger,
+                    Priority::Medium => text::primary,
+                    Priority::Low | Priority::None => text::secondary,
+                };
+                let task_row = row![
+                    button(done_icon)
+                        .on_press(Message::Complete(task.id))
+                        .style(task_style),
+                    text(task.priority.marker()).style(priority_style),
+                    button(
+                        column![text(&task.desc)].push_maybe(
+                            (task.project.is_some()
+                                || !task.tags.is_empty()
+                                || task.recurrence.is_some())
+                            .then(|| {
+                                text(task.format_details())
+                                    .size(12)
+                                    .shaping(text::Shaping::Advanced)
+                            })
+                        )
+                    )
+                    .style(task_style)
+                    .width(Length::Fill)
+                    .on_press(Message::Select(task.id)),
+                    text(task.format_subtasks()).shaping(text::Shaping::Advanced),
+                ]
+                .push_maybe((!task.notes.is_empty()).then(|| {
+                    button(text("✎").shaping(text::Shaping::Advanced))
+                        .style(button::text)
+                        .on_press(Message::ToggleNotes(task.id))
+                }))
+                .extend([
+                    text(format_interruptions(task.interruptions)).into(),
+                    text(task.format_pomodoros())
+                        .shaping(text::Shaping::Advanced)
+                        .into(),
+                    text(format_duration(task.spent)).into(),
+                    button(text("⋯").shaping(text::Shaping::Advanced))
+                        .on_press(Message::Edit(task.id))
+                        .into(),
+                    button(text("×").shaping(text::Shaping::Advanced))
+                        .style(button::danger)
+                        .on_press(Message::Delete(task.id))
+                        .into(),
+                ])
+                .spacing(10)
+                .align_y(Center);
+
+                // Expanded notes are shown below the task, indented like the subtasks.
+                let notes = (self.expanded.contains(&task.id) && !task.notes.is_empty())
+                    .then(|| container(text(&task.notes).size(14)).padding([0, 30]));
+
+                column![task_row]
+                    .push_maybe(notes)
+                    .push_maybe(self.view_subtasks(task))
                     .spacing(10)
-                    .align_y(Center)
                     .into()
-                }
-            };
-            (task.id, view)
-        });
+            }
+        }
+    }
 
-        let add_fields = if self.tasks.len() <= 10 {
+    /// View section for the task list and input form.
+    fn view_tasks(&self) -> Element<'_, Message> {
+        let tasks_list = self
+            .visible_tasks()
+            .map(|task| (task.id, self.view_task(task)));
+
+        let add_fields = if self.is_full() {
             row![
-                text_input("What are you working on?", &self.input)
+                text_input("Let’s finish current tasks first!", &self.input),
+                button("Add")
+            ]
+        } else {
+            row![
+                text_input("What are you working on? #tag @project", &self.input)
                     .id(self.input_id.clone())
                     .on_input(Message::Input)
                     .on_submit(Message::Add),
                 button("Add").on_press(Message::Add)
             ]
-        } else {
-            row![
-                text_input("Let’s finish current tasks first!", &self.input),
-                button("Add")
-            ]
         };
 
         column![
@@ -633,17 +2005,185 @@ impl Pomodoro {
                     .style(button::danger)
             ]
             .spacing(10),
-            keyed_column(tasks_list).spacing(10),
+            row![
+                pick_list(self.filters(), Some(&self.filter), Message::FilterSelected),
+                pick_list(SortKey::ALL, None::<SortKey>, Message::Sort).placeholder("Sort by..."),
+            ]
+            .spacing(10),
+            row![
+                text_input("Filter tasks...", &self.search)
+                    .id(self.search_id.clone())
+                    .on_input(Message::SearchInput),
+                checkbox("Show completed", self.show_completed)
+                    .on_toggle(|_| Message::ToggleCompleted),
+            ]
+            .spacing(10)
+            .align_y(Center),
+            scrollable(keyed_column(tasks_list).spacing(10))
+                .id(self.list_id.clone())
+                .height(Length::Fill)
+                .spacing(5),
+        ]
+        .push_maybe(self.view_upcoming())
+        .push(
             container(
                 button("End Day")
                     .on_press(Message::EndDay)
-                    .style(button::success)
+                    .style(button::success),
             )
             .center_x(Length::Fill),
-        ]
+        )
         .spacing(20)
         .into()
     }
+
+    /// View section for picking how often the edited task recurs.
+    fn view_recurrence(task: &Task) -> Element<'_, Message> {
+        let task_id = task.id;
+        let kind = task
+            .recurrence
+            .as_ref()
+            .map_or(RecurrenceKind::None, Recurrence::kind);
+        let schedule = row![pick_list(RecurrenceKind::ALL, Some(kind), move |kind| {
+            Message::RecurrenceSelected(task_id, kind)
+        })]
+        .spacing(5)
+        .align_y(Center);
+        match &task.recurrence {
+            Some(Recurrence::Days(days)) => schedule
+                .extend(WEEK.into_iter().map(|day| {
+                    let style = if days.contains(&day) {
+                        button::primary
+                    } else {
+                        button::secondary
+                    };
+                    button(text(day.to_string()).size(12))
+                        .style(style)
+                        .on_press(Message::RecurrenceDayToggled(task_id, day))
+                        .into()
+                }))
+                .into(),
+            Some(Recurrence::Every(days)) => schedule
+                .push(
+                    number_input(days, 1..=365, move |days| {
+                        Message::RecurrenceEveryChanged(task_id, days)
+                    })
+                    .width(70),
+                )
+                .push(text("days"))
+                .into(),
+            _ => schedule.into(),
+        }
+    }
+
+    /// View section for the completed recurring tasks waiting for their next occurrence.
+    fn view_upcoming(&self) -> Option<Element<'_, Message>> {
+        if self.upcoming.is_empty() {
+            return None;
+        }
+        let rows = self.upcoming.iter().enumerate().map(|(index, upcoming)| {
+            row![
+                text(&upcoming.task.desc).width(Length::Fill),
+                text(upcoming.due.format("%a %Y-%m-%d").to_string()).size(12),
+                button(text("×").shaping(text::Shaping::Advanced))
+                    .style(button::text)
+                    .on_press(Message::DeleteUpcoming(index)),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        });
+        Some(
+            column![text("Upcoming").size(16)]
+                .extend(rows)
+                .spacing(5)
+                .into(),
+        )
+    }
+
+    /// View section for the checklist of the active task or the task whose subtask is being
+    /// edited, `None` for all other tasks.
+    fn view_subtasks<'a>(&'a self, task: &'a Task) -> Option<Element<'a, Message>> {
+        let editing = self
+            .subtask_editing
+            .as_ref()
+            .filter(|(id, ..)| *id == task.id);
+        if self.active != Some(task.id) && editing.is_none() {
+            return None;
+        }
+        let selected = self.selected_subtask();
+        let input = |desc| {
+            row![
+                text_input("Subtask...", desc)
+                    .id(self.subtask_id.clone())
+                    .on_input(Message::SubtaskInput)
+                    .on_submit(Message::SaveSubtask),
+                button("Save").on_press(Message::SaveSubtask),
+                button("Cancel").on_press(Message::CancelSubtask),
+            ]
+            .spacing(10)
+            .align_y(Center)
+        };
+
+        let mut checklist = column![].spacing(5);
+        for (index, subtask) in task.subtasks.iter().enumerate() {
+            let subtask_row = match editing {
+                Some((_, Some(edited), desc)) if *edited == index => input(desc),
+                _ => {
+                    let marker = if selected == Some((task.id, index)) {
+                        "›"
+                    } else {
+                        " "
+                    };
+                    row![
+                        text(marker).font(iced::Font::MONOSPACE),
+                        checkbox(&subtask.desc, subtask.done)
+                            .on_toggle(move |_| Message::ToggleSubtask(task.id, index))
+                            .width(Length::Fill),
+                        button(text("⋯").shaping(text::Shaping::Advanced))
+                            .style(button::text)
+                            .on_press(Message::EditSubtask(task.id, index)),
+                        button(text("×").shaping(text::Shaping::Advanced))
+                            .style(button::text)
+                            .on_press(Message::DeleteSubtask(task.id, index)),
+                    ]
+                    .spacing(10)
+                    .align_y(Center)
+                }
+            };
+            checklist = checklist.push(subtask_row);
+        }
+        checklist = match editing {
+            Some((_, None, desc)) => checklist.push(input(desc)),
+            _ => checklist.push(
+                button("+ Subtask")
+                    .style(button::text)
+                    .on_press(Message::NewSubtask(task.id)),
+            ),
+        };
+
+        Some(container(checklist).padding([0, 30]).into())
+    }
+}
+
+/// Splits the quick-add text into the description, the `@project`, and the `#tags`,
+/// e.g. `Fix login bug #backend @acme`. The last project wins if several are given.
+fn parse_labels(input: &str) -> (String, Option<String>, Vec<String>) {
+    let mut words = Vec::new();
+    let mut project = None;
+    let mut tags: Vec<String> = Vec::new();
+    for word in input.split_whitespace() {
+        if let Some(name) = word.strip_prefix('@').filter(|name| !name.is_empty()) {
+            project = Some(name.to_string());
+        } else if let Some(name) = word.strip_prefix('#').filter(|name| !name.is_empty()) {
+            if !tags.iter().any(|tag| tag == name) {
+                tags.push(name.to_string());
+            }
+        } else {
+            words.push(word);
+        }
+    }
+    (words.join(" "), project, tags)
 }
 
 /// Formats a `Duration` into an `HH:MM:SS` string.
@@ -654,3 +2194,15 @@ fn format_duration(duration: Duration) -> String {
     let seconds = total_secs % 60;
     format!("{hours:0>2}:{minutes:0>2}:{seconds:0>2}")
 }
+
+/// Formats interruption counts in the Pomodoro Technique notation, e.g. `''-` for
+/// two internal and one external interruption.
+fn format_interruptions(interruptions: Interruptions) -> String {
+    let internal = usize::try_from(interruptions.internal).unwrap_or(usize::MAX);
+    let external = usize::try_from(interruptions.external).unwrap_or(usize::MAX);
+    if internal.saturating_add(external) > 5 {
+        format!("{internal}' {external}-")
+    } else {
+        format!("{}{}", "'".repeat(internal), "-".repeat(external))
+    }
+}
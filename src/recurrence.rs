@@ -0,0 +1,165 @@
+//! Describes how often a recurring task comes due again.
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// All days of the week, starting on Monday.
+pub const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// The schedule of a recurring task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    /// The given days of the week, every day if none is given.
+    Days(Vec<Weekday>),
+    /// Every given number of days.
+    Every(u16),
+}
+
+impl Recurrence {
+    /// Returns the first day after `done` on which the next occurrence is due.
+    pub fn next_due(&self, done: NaiveDate) -> NaiveDate {
+        let step = match self {
+            Self::Every(days) => u64::from(*days).max(1),
+            _ => 1,
+        };
+        let earliest = done.checked_add_days(Days::new(step)).unwrap_or(done);
+        // Any day of the week comes around within seven days.
+        earliest
+            .iter_days()
+            .take(7)
+            .find(|date| self.is_due_on(*date))
+            .unwrap_or(earliest)
+    }
+
+    /// Returns whether the schedule includes the day of the week of `date`.
+    fn is_due_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Daily | Self::Every(_) => true,
+            Self::Weekdays => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+            Self::Days(days) => days.is_empty() || days.contains(&date.weekday()),
+        }
+    }
+
+    /// Returns the kind of schedule, used to pick it from a list.
+    pub fn kind(&self) -> RecurrenceKind {
+        match self {
+            Self::Daily => RecurrenceKind::Daily,
+            Self::Weekdays => RecurrenceKind::Weekdays,
+            Self::Days(_) => RecurrenceKind::Days,
+            Self::Every(_) => RecurrenceKind::Every,
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Days(days) if days.is_empty() => write!(f, "daily"),
+            Self::Weekdays => write!(f, "on weekdays"),
+            Self::Days(days) => {
+                let days: Vec<String> = days.iter().map(ToString::to_string).collect();
+                write!(f, "on {}", days.join(", "))
+            }
+            Self::Every(days) => write!(f, "every {days} days"),
+        }
+    }
+}
+
+/// The kinds of schedule a task can have, `None` if it does not recur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceKind {
+    None,
+    Daily,
+    Weekdays,
+    Days,
+    Every,
+}
+
+impl RecurrenceKind {
+    pub const ALL: [Self; 5] = [
+        Self::None,
+        Self::Daily,
+        Self::Weekdays,
+        Self::Days,
+        Self::Every,
+    ];
+
+    /// Returns the schedule of this kind, starting on `today` for specific days.
+    pub fn recurrence(self, today: NaiveDate) -> Option<Recurrence> {
+        match self {
+            Self::None => None,
+            Self::Daily => Some(Recurrence::Daily),
+            Self::Weekdays => Some(Recurrence::Weekdays),
+            Self::Days => Some(Recurrence::Days(vec![today.weekday()])),
+            Self::Every => Some(Recurrence::Every(2)),
+        }
+    }
+}
+
+impl std::fmt::Display for RecurrenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "Does not repeat",
+            Self::Daily => "Daily",
+            Self::Weekdays => "Weekdays",
+            Self::Days => "Days of the week",
+            Self::Every => "Every N days",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        // 2026-10-12 is a Monday.
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn daily_is_due_the_next_day() {
+        assert_eq!(Recurrence::Daily.next_due(date(12)), date(13));
+    }
+
+    #[test]
+    fn weekdays_skip_the_weekend() {
+        assert_eq!(Recurrence::Weekdays.next_due(date(15)), date(16));
+        assert_eq!(Recurrence::Weekdays.next_due(date(16)), date(19));
+        assert_eq!(Recurrence::Weekdays.next_due(date(17)), date(19));
+    }
+
+    #[test]
+    fn every_n_days_counts_from_the_day_done() {
+        assert_eq!(Recurrence::Every(3).next_due(date(12)), date(15));
+        assert_eq!(Recurrence::Every(0).next_due(date(12)), date(13));
+    }
+
+    #[test]
+    fn days_wrap_into_the_next_week() {
+        let days = Recurrence::Days(vec![Weekday::Mon, Weekday::Wed]);
+        assert_eq!(days.next_due(date(12)), date(14));
+        assert_eq!(days.next_due(date(14)), date(19));
+        assert_eq!(days.next_due(date(17)), date(19));
+    }
+
+    #[test]
+    fn empty_days_recur_daily() {
+        let days = Recurrence::Days(Vec::new());
+        assert_eq!(days.next_due(date(12)), date(13));
+        assert_eq!(days.to_string(), "daily");
+    }
+}
@@ -1,20 +1,154 @@
 //! Manages the report generating, storing, and viewing productivity reports, including streaks and focused time.
 
 use super::persistence;
+use super::timer::Interruptions;
 
 use iced::time::Duration;
-use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text};
+use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text, text_input};
 use iced::{Center, Element, Length};
 
 use chrono::{Days, NaiveDate};
 use serde::{Deserialize, Serialize};
 
+use std::collections::BTreeMap;
+
+/// A daily target of pomodoros and focused time, where zero turns a target off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Goal {
+    pub pomodoros: u32,
+    pub focused: Duration,
+}
+
+impl Goal {
+    /// Returns whether any target is set.
+    pub fn is_set(&self) -> bool {
+        self.pomodoros > 0 || !self.focused.is_zero()
+    }
+
+    /// Returns whether the work of a day reaches every target that is set.
+    pub fn is_met(&self, pomodoros: u32, focused: Duration) -> bool {
+        self.is_set() && pomodoros >= self.pomodoros && focused >= self.focused
+    }
+}
+
+/// Focused time of the completed occurrences of a recurring task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeriesTime {
+    /// Description of the latest occurrence.
+    pub name: String,
+    pub spent: Duration,
+    pub occurrences: u32,
+}
+
+impl SeriesTime {
+    /// Adds the time of later occurrences, taking over their description.
+    pub fn merge(&mut self, later: Self) {
+        self.name = later.name;
+        self.spent = self.spent.saturating_add(later.spent);
+        self.occurrences = self.occurrences.saturating_add(later.occurrences);
+    }
+}
+
+/// Totals of the work done since the last report, added to today's report.
+#[derive(Debug, Clone, Default)]
+pub struct DaySummary {
+    pub focused: Duration,
+    pub overtime: Duration,
+    /// Part of the overtime included in the focused time.
+    pub counted_overtime: Duration,
+    /// Part of the focused time spent without an active task.
+    pub unassigned: Duration,
+    /// Number of completed tasks.
+    pub completed: usize,
+    /// Number of work sessions finished during the whole day.
+    pub pomodoros: u32,
+    pub interruptions: Interruptions,
+    pub goal_met: bool,
+    /// Pomodoros estimated for the completed tasks that had an estimate.
+    pub estimated: u32,
+    /// Pomodoros actually spent on the completed tasks that had an estimate.
+    pub estimate_actual: u32,
+    /// Focused time of the completed tasks per project.
+    pub projects: BTreeMap<String, Duration>,
+    /// Focused time of the completed tasks per tag, a task with several tags counts for each.
+    pub tags: BTreeMap<String, Duration>,
+    /// Focused time of the completed recurring tasks per series.
+    pub series: BTreeMap<u64, SeriesTime>,
+}
+
+impl DaySummary {
+    /// Returns whether there is nothing to report.
+    pub fn is_empty(&self) -> bool {
+        self.completed == 0 && self.pomodoros == 0 && self.unassigned.is_zero()
+    }
+}
+
 /// Represents the productivity data collected for a single day.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DayReport {
     date: NaiveDate,
     focused: Duration,
+    #[serde(default)]
+    overtime: Duration,
+    /// Part of the overtime included in the focused time.
+    #[serde(default)]
+    counted_overtime: Duration,
+    /// Part of the focused time spent without an active task.
+    #[serde(default)]
+    unassigned: Duration,
     completed: usize,
+    #[serde(default)]
+    interruptions: Interruptions,
+    #[serde(default)]
+    pomodoros: u32,
+    /// Whether the daily goal was met, kept as it was even if the goal changes later.
+    #[serde(default)]
+    goal_met: bool,
+    #[serde(default)]
+    estimated: u32,
+    #[serde(default)]
+    estimate_actual: u32,
+    #[serde(default)]
+    projects: BTreeMap<String, Duration>,
+    #[serde(default)]
+    tags: BTreeMap<String, Duration>,
+    #[serde(default)]
+    series: BTreeMap<u64, SeriesTime>,
+}
+
+impl DayReport {
+    /// Builds a single row of the history table.
+    fn view(&self) -> Element<'_, Message> {
+        let goal_mark = if self.goal_met { " ✓" } else { "" };
+        row![
+            text!("{}{goal_mark}", self.date.format("%Y-%m-%d"))
+                .shaping(text::Shaping::Advanced)
+                .width(Length::Fill),
+            // The overtime has a column of its own, even if it counts as focused time.
+            text(format_duration(
+                self.focused.saturating_sub(self.counted_overtime),
+            ))
+            .width(Length::Fill),
+            text(format_duration(self.overtime)).width(Length::Fill),
+            text(format_duration(self.unassigned)).width(Length::Fill),
+            text(self.pomodoros.to_string())
+                .width(Length::Fill)
+                .align_x(Center),
+            text(self.completed.to_string())
+                .width(Length::Fill)
+                .align_x(Center),
+            text!(
+                "{}' {}-",
+                self.interruptions.internal,
+                self.interruptions.external
+            )
+            .width(Length::Fill)
+            .align_x(Center),
+        ]
+        .spacing(10)
+        .padding(5)
+        .into()
+    }
 }
 
 /// Stores the complete Pomodoro usage history and summary statistics.
@@ -26,6 +160,15 @@ pub struct Report {
 
     #[serde(skip)]
     error: String,
+    /// Whether only days that met the daily goal count toward streaks.
+    #[serde(skip)]
+    streak_goal_only: bool,
+    /// First day of the project and tag breakdown as typed, empty for no limit.
+    #[serde(skip)]
+    range_from: String,
+    /// Last day of the project and tag breakdown as typed, empty for no limit.
+    #[serde(skip)]
+    range_to: String,
 }
 
 impl Default for Report {
@@ -35,14 +178,21 @@ impl Default for Report {
             longest_streak: 0,
             longest_focused: Duration::from_secs(0),
             error: String::new(),
+            streak_goal_only: false,
+            range_from: String::new(),
+            range_to: String::new(),
         }
     }
 }
 
 /// Messages used for updating the report tab.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
-    Generate { focused: Duration, completed: usize },
+    Generate(DaySummary),
+    RangeFromChanged(String),
+    RangeToChanged(String),
+    /// Sets the breakdown range to the last given number of days, or all days if `None`.
+    RangeLastDays(Option<u64>),
     Clear,
     Import,
     Export,
@@ -54,37 +204,78 @@ impl Report {
         persistence::load("reports.json").unwrap_or_default()
     }
 
+    /// Brings back the history and records of an earlier or imported state, keeping the
+    /// current settings and inputs.
+    pub fn restore(&mut self, saved: Self) {
+        self.history = saved.history;
+        self.longest_streak = saved.longest_streak;
+        self.longest_focused = saved.longest_focused;
+        self.error.clear();
+        let _ = persistence::save("reports.json", &self);
+    }
+
+    /// Sets whether only days that met the daily goal count toward streaks.
+    pub fn set_streak_goal_only(&mut self, goal_only: bool) {
+        self.streak_goal_only = goal_only;
+    }
+
     /// Processes messages and updates the component's state.
     pub fn update(&mut self, message: Message) {
         match message {
-            Message::Generate { completed, focused } => {
+            Message::Generate(summary) => {
                 let today = chrono::Local::now().date_naive();
 
                 let current_focused = if let Some(report) =
                     self.history.iter_mut().find(|report| report.date == today)
                 {
                     // Found today's report, update focused/completed values.
-                    report.focused = report.focused.saturating_add(focused);
-                    report.completed = report.completed.saturating_add(completed);
+                    report.focused = report.focused.saturating_add(summary.focused);
+                    report.overtime = report.overtime.saturating_add(summary.overtime);
+                    report.counted_overtime = report
+                        .counted_overtime
+                        .saturating_add(summary.counted_overtime);
+                    report.unassigned = report.unassigned.saturating_add(summary.unassigned);
+                    report.completed = report.completed.saturating_add(summary.completed);
+                    report.interruptions = report.interruptions.merged(summary.interruptions);
+                    // The pomodoros are counted for the whole day, not since the last report.
+                    report.pomodoros = report.pomodoros.max(summary.pomodoros);
+                    report.goal_met |= summary.goal_met;
+                    report.estimated = report.estimated.saturating_add(summary.estimated);
+                    report.estimate_actual = report
+                        .estimate_actual
+                        .saturating_add(summary.estimate_actual);
+                    merge_times(&mut report.projects, summary.projects);
+                    merge_times(&mut report.tags, summary.tags);
+                    merge_series(&mut report.series, summary.series);
                     report.focused
                 } else {
                     // New day, add new report.
                     self.history.push(DayReport {
                         date: today,
-                        focused,
-                        completed,
+                        focused: summary.focused,
+                        overtime: summary.overtime,
+                        counted_overtime: summary.counted_overtime,
+                        unassigned: summary.unassigned,
+                        completed: summary.completed,
+                        interruptions: summary.interruptions,
+                        pomodoros: summary.pomodoros,
+                        goal_met: summary.goal_met,
+                        estimated: summary.estimated,
+                        estimate_actual: summary.estimate_actual,
+                        projects: summary.projects,
+                        tags: summary.tags,
+                        series: summary.series,
                     });
                     self.history.sort_by_key(|report| report.date);
-
-                    // Update longest streak if current streak is longer
-                    let streak = self.calculate_current_streak(today);
-                    if streak > self.longest_streak {
-                        self.longest_streak = streak;
-                    }
-
-                    focused
+                    summary.focused
                 };
 
+                // Update longest streak if current streak is longer
+                let streak = self.calculate_current_streak(today);
+                if streak > self.longest_streak {
+                    self.longest_streak = streak;
+                }
+
                 if current_focused > self.longest_focused {
                     self.longest_focused = current_focused;
                 }
@@ -92,6 +283,16 @@ impl Report {
                 let _ = persistence::save("reports.json", &self);
                 self.error.clear();
             }
+            Message::RangeFromChanged(from) => self.range_from = from,
+            Message::RangeToChanged(to) => self.range_to = to,
+            Message::RangeLastDays(days) => {
+                let today = chrono::Local::now().date_naive();
+                self.range_from = days
+                    .and_then(|days| today.checked_sub_days(Days::new(days.saturating_sub(1))))
+                    .map(|from| from.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                self.range_to.clear();
+            }
             Message::Clear => {
                 self.history.clear();
                 self.longest_streak = 0;
@@ -100,10 +301,7 @@ impl Report {
                 let _ = persistence::save("reports.json", &self);
             }
             Message::Import => match persistence::import::<Self>("reports.json") {
-                Ok(imported_data) => {
-                    *self = imported_data;
-                    self.error.clear();
-                }
+                Ok(imported_data) => self.restore(imported_data),
                 Err(_) => {
                     self.error = "Import failed! Please ensure the file is valid.".to_string();
                 }
@@ -116,9 +314,12 @@ impl Report {
     }
 
     /// Calculates the current number of consecutive days with a report, ending with `today`.
+    ///
+    /// Days that missed the daily goal break the streak if only goal-met days count.
     fn calculate_current_streak(&self, today: NaiveDate) -> usize {
         self.history
             .iter()
+            .filter(|report| report.goal_met || !self.streak_goal_only)
             .rev() // Iterate backward through the history to check for continuity
             .scan(Some(today), |expected, report| match *expected {
                 Some(date) if report.date == date => {
@@ -130,6 +331,148 @@ impl Report {
             .count()
     }
 
+    /// Builds the estimation accuracy of the last days with estimated tasks, if there are any.
+    ///
+    /// The accuracy is the actual pomodoros as a percentage of the estimated ones, so values
+    /// above 100% mean the tasks were underestimated.
+    fn view_estimates(&self) -> Option<Element<'_, Message>> {
+        let estimated_days: Vec<&DayReport> = self
+            .history
+            .iter()
+            .filter(|report| report.estimated > 0)
+            .collect();
+        if estimated_days.is_empty() {
+            return None;
+        }
+        let accuracy =
+            |actual: u32, estimated: u32| u64::from(actual) * 100 / u64::from(estimated).max(1);
+
+        let estimated: u32 = estimated_days.iter().map(|report| report.estimated).sum();
+        let actual: u32 = estimated_days
+            .iter()
+            .map(|report| report.estimate_actual)
+            .sum();
+        let day_rows = estimated_days.iter().rev().take(7).map(|report| {
+            row![
+                text(report.date.format("%Y-%m-%d").to_string()).width(Length::Fill),
+                text!("{}/{} 🍅", report.estimate_actual, report.estimated)
+                    .shaping(text::Shaping::Advanced)
+                    .width(Length::Fill),
+                text!("{}%", accuracy(report.estimate_actual, report.estimated))
+                    .width(Length::Fill)
+                    .align_x(Center),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+        Some(
+            column![
+                text("Estimates").size(24),
+                horizontal_rule(1),
+                text!(
+                    "Overall: {actual}/{estimated} 🍅 ({}% of the estimate)",
+                    accuracy(actual, estimated)
+                )
+                .shaping(text::Shaping::Advanced)
+                .size(18),
+                column(day_rows).spacing(5),
+            ]
+            .spacing(10)
+            .into(),
+        )
+    }
+
+    /// Builds the focused time per project, per tag, and per recurring task over the chosen
+    /// date range.
+    fn view_breakdown(&self) -> Element<'_, Message> {
+        let parse = |date: &str| {
+            let date = date.trim();
+            if date.is_empty() {
+                Ok(None)
+            } else {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").map(Some)
+            }
+        };
+        let range_inputs = column![
+            row![
+                text_input("From YYYY-MM-DD", &self.range_from).on_input(Message::RangeFromChanged),
+                text_input("To YYYY-MM-DD", &self.range_to).on_input(Message::RangeToChanged),
+            ]
+            .spacing(10),
+            row![
+                button("Last 7 days").on_press(Message::RangeLastDays(Some(7))),
+                button("Last 30 days").on_press(Message::RangeLastDays(Some(30))),
+                button("All time").on_press(Message::RangeLastDays(None)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        let content = match (parse(&self.range_from), parse(&self.range_to)) {
+            (Ok(from), Ok(to)) => {
+                let mut projects = BTreeMap::new();
+                let mut tags = BTreeMap::new();
+                let mut series = BTreeMap::new();
+                for report in self.history.iter().filter(|report| {
+                    from.is_none_or(|from| report.date >= from)
+                        && to.is_none_or(|to| report.date <= to)
+                }) {
+                    merge_times(&mut projects, report.projects.clone());
+                    merge_times(&mut tags, report.tags.clone());
+                    merge_series(&mut series, report.series.clone());
+                }
+                if projects.is_empty() && tags.is_empty() && series.is_empty() {
+                    column![text("No projects, tags, or recurring tasks in this range.")]
+                } else {
+                    let time_rows = |mut times: Vec<(String, Duration)>| {
+                        times.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+                        column(times.into_iter().map(|(name, time)| {
+                            row![
+                                text(name)
+                                    .shaping(text::Shaping::Advanced)
+                                    .width(Length::Fill),
+                                text(format_duration(time)),
+                            ]
+                            .spacing(10)
+                            .into()
+                        }))
+                        .spacing(5)
+                    };
+                    let labeled = |times: BTreeMap<String, Duration>, prefix: &str| {
+                        times
+                            .into_iter()
+                            .map(|(name, time)| (format!("{prefix}{name}"), time))
+                            .collect()
+                    };
+                    let series = series
+                        .into_values()
+                        .map(|series| {
+                            let name = format!("↻ {} ({}×)", series.name, series.occurrences);
+                            (name, series.spent)
+                        })
+                        .collect();
+                    column![
+                        time_rows(labeled(projects, "@")),
+                        time_rows(labeled(tags, "#")),
+                        time_rows(series),
+                    ]
+                    .spacing(10)
+                }
+            }
+            _ => column![text("Dates must be written as YYYY-MM-DD.").style(text::danger)],
+        };
+
+        column![
+            text("Projects, Tags, and Recurring Tasks").size(24),
+            horizontal_rule(1),
+            range_inputs,
+            content,
+        ]
+        .spacing(10)
+        .into()
+    }
+
     /// Builds the report summary, history table, and control buttons.
     pub fn view(&self) -> Element<'_, Message> {
         let mut content = if self.history.is_empty() {
@@ -181,28 +524,17 @@ impl Report {
             let table_header = row![
                 text("Date").width(Length::Fill),
                 text("Focused Time").width(Length::Fill),
+                text("Overtime").width(Length::Fill),
+                text("Unassigned").width(Length::Fill),
                 text("Pomodoros").width(Length::Fill).align_x(Center),
+                text("Completed").width(Length::Fill).align_x(Center),
+                text("Interrupts").width(Length::Fill).align_x(Center),
             ]
             .spacing(10);
 
             // Generate report rows from history, showing most recent first.
-            let report_rows: Vec<Element<_>> = self
-                .history
-                .iter()
-                .rev()
-                .map(|report| {
-                    row![
-                        text(report.date.format("%Y-%m-%d").to_string()).width(Length::Fill),
-                        text(format_duration(report.focused)).width(Length::Fill),
-                        text(report.completed.to_string())
-                            .width(Length::Fill)
-                            .align_x(Center),
-                    ]
-                    .spacing(10)
-                    .padding(5)
-                    .into()
-                })
-                .collect();
+            let report_rows: Vec<Element<_>> =
+                self.history.iter().rev().map(DayReport::view).collect();
 
             let reports_list = column(report_rows).spacing(5);
             let history_buttons = container(
@@ -219,14 +551,18 @@ impl Report {
             column![
                 text("Summary").size(24),
                 horizontal_rule(1),
-                summary_section,
-                text("History").size(24),
-                horizontal_rule(1),
-                table_header,
-                horizontal_rule(1),
-                scrollable(reports_list),
-                history_buttons
+                summary_section
             ]
+            .push_maybe(self.view_estimates())
+            .extend([
+                self.view_breakdown(),
+                text("History").size(24).into(),
+                horizontal_rule(1).into(),
+                table_header.into(),
+                horizontal_rule(1).into(),
+                scrollable(reports_list).into(),
+                history_buttons.into(),
+            ])
             .spacing(10)
         };
 
@@ -242,6 +578,21 @@ impl Report {
     }
 }
 
+/// Adds the time of each name to the running totals.
+fn merge_times(totals: &mut BTreeMap<String, Duration>, times: BTreeMap<String, Duration>) {
+    for (name, time) in times {
+        let total = totals.entry(name).or_insert(Duration::ZERO);
+        *total = total.saturating_add(time);
+    }
+}
+
+/// Adds the time of each recurring series to the running totals.
+fn merge_series(totals: &mut BTreeMap<u64, SeriesTime>, series: BTreeMap<u64, SeriesTime>) {
+    for (id, time) in series {
+        totals.entry(id).or_default().merge(time);
+    }
+}
+
 /// Formats a `Duration` into an `HH:MM:SS` string.
 fn format_duration(duration: Duration) -> String {
     let total_secs = duration.as_secs();
@@ -0,0 +1,54 @@
+//! Keeps an append-only log of every finished work and break session.
+
+use super::persistence;
+use super::timer::{Interruptions, Session, Summary};
+
+use iced::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Name of the JSON lines file holding the session log.
+const LOG_FILE: &str = "sessions.jsonl";
+
+/// A single finished session with its timing details and the task it was spent on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    session: Session,
+    #[serde(default)]
+    step: String,
+    task_id: Option<u64>,
+    task_desc: Option<String>,
+    paused: Duration,
+    #[serde(default)]
+    pauses: u32,
+    #[serde(default)]
+    interruptions: Interruptions,
+    overtime: Duration,
+}
+
+impl SessionRecord {
+    /// Creates a record from a finished session, returning `None` if it was never started.
+    pub fn new(summary: &Summary, task: Option<(u64, String)>) -> Option<Self> {
+        let (task_id, task_desc) = task.unzip();
+        Some(Self {
+            start: summary.started_at?,
+            end: summary.ended_at,
+            session: summary.session,
+            step: summary.step.clone(),
+            task_id,
+            task_desc,
+            paused: summary.paused,
+            pauses: summary.pauses,
+            interruptions: summary.interruptions,
+            overtime: summary.spent.overtime,
+        })
+    }
+}
+
+/// Appends a finished session to the log in the app's data directory.
+pub fn append(record: &SessionRecord) {
+    let _ = persistence::append(LOG_FILE, record);
+}
@@ -1,13 +1,27 @@
-//! Manages application settings, including themes, timer durations, and keyboard shortcuts.
+//! Manages application settings, including named profiles, themes, timer durations,
+//! session sequences, notifications, and keyboard shortcuts.
 
+use super::notification::{self, Alerts, Messages, Trigger, Urgency, WarningSetting};
 use super::persistence;
+use super::pomodoro;
+use super::report::Goal;
+use super::timer::{AutoStart, BreakRule, OvertimePolicy, Session, Step};
 
-use iced::widget::{button, column, container, horizontal_rule, radio, row, scrollable, text};
-use iced::{Element, Length, Theme};
+use iced::time::Duration;
+use iced::widget::{
+    button, checkbox, column, container, horizontal_rule, pick_list, radio, row, scrollable, text,
+    text_input,
+};
+use iced::{Element, Length, Subscription, Theme};
 use iced_aw::widget::number_input;
 
 use serde::{Deserialize, Serialize};
 
+use std::time::SystemTime;
+
+/// Name of the file holding the settings profiles, watched for changes made outside the app.
+const SETTINGS_FILE: &str = "settings.json";
+
 /// List of all available themes and their display names, used in UI rendering.
 const ALL_THEMES: [(&str, AppTheme); 10] = [
     ("Catppuccin Frappe", AppTheme::CatppuccinFrappe),
@@ -37,6 +51,16 @@ pub enum AppTheme {
     TokyoNightLight,
 }
 
+impl std::fmt::Display for AppTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = ALL_THEMES
+            .iter()
+            .find(|(_, theme)| theme == self)
+            .map_or("", |(name, _)| name);
+        write!(f, "{name}")
+    }
+}
+
 impl AppTheme {
     /// Converts the custom `AppTheme` into the corresponding `iced::Theme`.
     pub fn to_iced_theme(self) -> Theme {
@@ -55,6 +79,11 @@ impl AppTheme {
     }
 }
 
+/// Converts whole minutes into a `Duration`.
+fn minutes(value: u8) -> Duration {
+    Duration::from_secs(u64::from(value) * 60)
+}
+
 /// Indicates which session type a setting applies to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionType {
@@ -62,8 +91,80 @@ pub enum SessionType {
     Break,
 }
 
+/// A single step of a user-defined session sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateStep {
+    pub name: String,
+    pub session: Session,
+    pub minutes: u8,
+    pub theme: AppTheme,
+}
+
+/// A named, user-defined sequence of sessions (e.g. "52/17" or a 90-minute ultradian cycle).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    pub steps: Vec<TemplateStep>,
+}
+
+/// Defines how the break earned in Flowtime mode is calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlowBreakMode {
+    Ratio,
+    Table,
+}
+
+/// Stores how overtime is limited and logged, in minutes where zero turns a limit off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OvertimeSettings {
+    pub cap_min: u8,
+    pub auto_finish_min: u8,
+    pub counts_as_focus: bool,
+}
+
+impl Default for OvertimeSettings {
+    fn default() -> Self {
+        Self {
+            cap_min: 0,
+            auto_finish_min: 0,
+            counts_as_focus: true,
+        }
+    }
+}
+
+/// Stores the daily goal, where zero turns a target off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoalSettings {
+    pub pomodoros: u8,
+    pub focus_min: u16,
+    /// Whether only days that met the goal count toward streaks.
+    pub streak_goal_only: bool,
+}
+
+/// Stores how the task list behaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskSettings {
+    /// Whether a task is completed once all of its subtasks are ticked.
+    pub auto_complete: bool,
+    /// Most tasks the list can hold, zero for no limit.
+    pub max_tasks: u16,
+}
+
+impl Default for TaskSettings {
+    fn default() -> Self {
+        Self {
+            auto_complete: false,
+            max_tasks: 10,
+        }
+    }
+}
+
 /// Stores user-configurable settings for session durations and themes.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub work_min: u8,
     pub break_min: u8,
@@ -71,6 +172,26 @@ pub struct Settings {
     pub long_break_after: u8,
     pub work_theme: AppTheme,
     pub break_theme: AppTheme,
+    pub auto_start_breaks: bool,
+    pub auto_start_pomodoros: bool,
+    pub auto_start_grace_sec: u8,
+    pub templates: Vec<Template>,
+    /// Index of the template in use, `None` for the classic pomodoro cycle.
+    pub active_template: Option<usize>,
+    /// Count work sessions up until they are finished, then take an earned break.
+    pub flowtime: bool,
+    /// The earned break is the work time divided by this number.
+    pub flow_break_ratio: u8,
+    /// Whether the earned break comes from `flow_break_ratio` or `flow_break_table`.
+    pub flow_break_mode: FlowBreakMode,
+    /// Pairs of `(work up to, break)` in minutes.
+    pub flow_break_table: Vec<(u8, u8)>,
+    pub overtime: OvertimeSettings,
+    /// Language of the notification messages.
+    pub language: String,
+    pub notifications: Alerts,
+    pub goal: GoalSettings,
+    pub tasks: TaskSettings,
 }
 
 impl Default for Settings {
@@ -82,27 +203,331 @@ impl Default for Settings {
             long_break_after: 4,
             work_theme: AppTheme::SolarizedDark,
             break_theme: AppTheme::SolarizedLight,
+            auto_start_breaks: false,
+            auto_start_pomodoros: false,
+            auto_start_grace_sec: 0,
+            templates: Vec::new(),
+            active_template: None,
+            flowtime: false,
+            flow_break_ratio: 5,
+            flow_break_mode: FlowBreakMode::Ratio,
+            flow_break_table: vec![(25, 5), (50, 8), (90, 10), (240, 15)],
+            overtime: OvertimeSettings::default(),
+            language: notification::DEFAULT_LANGUAGE.to_string(),
+            notifications: Alerts::default(),
+            goal: GoalSettings::default(),
+            tasks: TaskSettings::default(),
         }
     }
 }
 
+/// A named set of settings, e.g. "deep work" or "meetings day".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub settings: Settings,
+}
+
+/// Stores all settings profiles together with the one currently in use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(rename = "profiles")]
+    entries: Vec<Profile>,
+    active: usize,
+
+    /// Notification message pools, used for previewing messages.
+    #[serde(skip)]
+    messages: Messages,
+    /// The last previewed notification message.
+    #[serde(skip)]
+    preview: String,
+    /// Why the settings file could not be read, empty if it was fine.
+    #[serde(skip)]
+    error: String,
+    /// When the settings file was last read or written, to notice changes made outside the app.
+    #[serde(skip)]
+    loaded: Option<SystemTime>,
+}
+
 /// Messages used for updating the settings tab.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
     PomodoroChanged(u8),
     BreakChanged(u8),
     LongBreakChanged(u8),
     LongBreakAfterChanged(u8),
     ThemeChanged(SessionType, AppTheme),
+    AutoStartToggled(SessionType, bool),
+    GraceChanged(u8),
+    FlowtimeToggled(bool),
+    FlowRatioChanged(u8),
+    FlowBreakModeChanged(FlowBreakMode),
+    FlowTableWorkChanged(usize, u8),
+    FlowTableBreakChanged(usize, u8),
+    AddFlowTableRow,
+    DeleteFlowTableRow(usize),
+    OvertimeCapChanged(u8),
+    OvertimeAutoFinishChanged(u8),
+    OvertimeFocusToggled(bool),
+    LanguageChanged(String),
+    Preview(Session),
+    NotifyToggled(Trigger, bool),
+    SoundChanged(Trigger, String),
+    UrgencyChanged(Trigger, Urgency),
+    NotifyTimeoutChanged(Trigger, u8),
+    OvertimeReminderChanged(u8),
+    OvertimeRepeatToggled(bool),
+    AddWarning,
+    DeleteWarning(usize),
+    WarningSessionChanged(usize, Session),
+    WarningSecondsChanged(usize, u16),
+    HighlightChanged(u16),
+    GoalPomodorosChanged(u8),
+    GoalFocusChanged(u16),
+    StreakGoalOnlyToggled(bool),
+    AutoCompleteToggled(bool),
+    MaxTasksChanged(u16),
+    TemplateSelected(Option<usize>),
+    AddTemplate,
+    DeleteTemplate,
+    TemplateNameChanged(String),
+    AddStep,
+    DeleteStep(usize),
+    StepNameChanged(usize, String),
+    StepSessionChanged(usize, Session),
+    StepMinutesChanged(usize, u8),
+    StepThemeChanged(usize, AppTheme),
+    ProfileSelected(usize),
+    NextProfile,
+    AddProfile,
+    DeleteProfile,
+    ProfileNameChanged(String),
+    Reload,
     Submit,
 }
 
-impl Settings {
-    /// Loads saved settings from persistent storage or returns default.
+impl Profiles {
+    /// Loads saved profiles from persistent storage or returns a single default profile.
     pub fn new() -> Self {
-        persistence::load("settings.json").unwrap_or_default()
+        let mut profiles = Self::single(Settings::default());
+        profiles.reload();
+        profiles.messages = Messages::load();
+        profiles
+    }
+
+    /// Creates the profiles with the given settings as the only one.
+    fn single(settings: Settings) -> Self {
+        Self {
+            entries: vec![Profile {
+                name: "Default".to_string(),
+                settings,
+            }],
+            active: 0,
+            messages: Messages::default(),
+            preview: String::new(),
+            error: String::new(),
+            loaded: None,
+        }
+    }
+
+    /// Reads the profiles from the settings file.
+    ///
+    /// Settings saved before profiles existed are read as the only profile.
+    fn read() -> Result<Self, String> {
+        let invalid = |err: &dyn std::fmt::Display| format!("{SETTINGS_FILE} is invalid: {err}");
+        let value: serde_json::Value =
+            persistence::load(SETTINGS_FILE).map_err(|err| invalid(&err))?;
+        let mut profiles = if value.get("profiles").is_some() {
+            serde_json::from_value::<Self>(value)
+        } else {
+            serde_json::from_value::<Settings>(value).map(Self::single)
+        }
+        .map_err(|err| invalid(&err))?;
+
+        if profiles.entries.is_empty() {
+            return Err(invalid(&"it has no profiles"));
+        }
+        profiles.active = profiles.active.min(profiles.entries.len() - 1);
+        Ok(profiles)
+    }
+
+    /// Reloads the profiles if the settings file changed since it was last read or written.
+    ///
+    /// An invalid file is reported in the settings tab, and the current profiles are kept.
+    fn reload(&mut self) {
+        let modified = persistence::modified(SETTINGS_FILE).ok();
+        if modified.is_none() || modified == self.loaded {
+            return;
+        }
+        self.loaded = modified;
+        match Self::read() {
+            Ok(profiles) => {
+                self.entries = profiles.entries;
+                self.active = profiles.active;
+                self.error.clear();
+            }
+            Err(error) => self.error = error,
+        }
+    }
+
+    /// Saves the profiles, remembering the time of the write so it is not reloaded.
+    fn save(&mut self) {
+        if persistence::save(SETTINGS_FILE, &self).is_ok() {
+            self.loaded = persistence::modified(SETTINGS_FILE).ok();
+            self.error.clear();
+        }
+    }
+
+    /// Checks the settings file for changes made outside the app.
+    pub fn subscription() -> Subscription<Message> {
+        iced::time::every(Duration::from_secs(1)).map(|_| Message::Reload)
+    }
+
+    /// Returns the settings of the profile in use.
+    pub fn active(&self) -> &Settings {
+        &self.entries[self.active].settings
     }
 
+    /// Processes profile messages and passes the rest on to the settings of the active profile.
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::ProfileSelected(index) => {
+                if index < self.entries.len() {
+                    self.active = index;
+                    self.save();
+                }
+            }
+            Message::NextProfile => {
+                self.active = (self.active + 1) % self.entries.len();
+                self.save();
+            }
+            Message::AddProfile => {
+                let settings = self.active().clone();
+                self.entries.push(Profile {
+                    name: format!("Profile {}", self.entries.len() + 1),
+                    settings,
+                });
+                self.active = self.entries.len() - 1;
+            }
+            Message::DeleteProfile => {
+                // Keep at least one profile around.
+                if self.entries.len() > 1 {
+                    self.entries.remove(self.active);
+                    self.active = self.active.saturating_sub(1);
+                }
+            }
+            Message::ProfileNameChanged(name) => self.entries[self.active].name = name,
+            Message::Preview(session) => {
+                self.preview = self.messages.pick(&self.active().language, session);
+            }
+            Message::Reload => self.reload(),
+            Message::Submit => self.save(),
+            _ => self.entries[self.active].settings.update(message),
+        }
+    }
+
+    /// Builds the main view for the Settings tab.
+    pub fn view(&self) -> Element<'_, Message> {
+        let settings = self.active();
+        // Report a settings file that could not be read above everything else.
+        let error = (!self.error.is_empty()).then(|| text(&self.error).style(text::danger));
+        scrollable(
+            column![]
+                .push_maybe(error)
+                .extend([
+                    self.view_profile_settings(),
+                    settings.view_timer_settings(),
+                    settings.view_sequence_settings(),
+                    settings.view_flowtime_settings(),
+                    settings.view_overtime_settings(),
+                    settings.view_goal_settings(),
+                    settings.view_task_settings(),
+                    self.view_message_settings(),
+                    settings.view_notification_settings(),
+                    settings.view_auto_start_settings(),
+                    settings.view_theme_settings(),
+                    Settings::view_shortcuts(),
+                    button("Save Settings").on_press(Message::Submit).into(),
+                ])
+                .spacing(20)
+                .padding(10),
+        )
+        .into()
+    }
+
+    /// View section showing the notification message pool in use, with a random preview.
+    fn view_message_settings(&self) -> Element<'_, Message> {
+        let language = &self.active().language;
+        let preview_button = |label, session| {
+            button(label)
+                .style(button::secondary)
+                .on_press(Message::Preview(session))
+        };
+
+        column![
+            text("Notification Messages").size(20),
+            horizontal_rule(1),
+            text(self.messages.describe(language)),
+            row![
+                text("Language"),
+                pick_list(
+                    self.messages.languages(),
+                    Some(language.clone()),
+                    Message::LanguageChanged
+                ),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            row![
+                text("Preview"),
+                preview_button("Work", Session::Pomodoro),
+                preview_button("Break", Session::Break),
+                preview_button("Long break", Session::LongBreak),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            text(&self.preview),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for picking, naming, adding, and deleting profiles.
+    fn view_profile_settings(&self) -> Element<'_, Message> {
+        let profile_radios = column(self.entries.iter().enumerate().map(|(i, profile)| {
+            radio(
+                profile.name.as_str(),
+                i,
+                Some(self.active),
+                Message::ProfileSelected,
+            )
+            .into()
+        }))
+        .spacing(5);
+
+        let delete_button = button("Delete profile").style(button::danger);
+        column![
+            text("Profiles").size(20),
+            horizontal_rule(1),
+            profile_radios,
+            text_input("Profile name", &self.entries[self.active].name)
+                .on_input(Message::ProfileNameChanged),
+            row![
+                button("New profile").on_press(Message::AddProfile),
+                if self.entries.len() > 1 {
+                    delete_button.on_press(Message::DeleteProfile)
+                } else {
+                    delete_button
+                },
+            ]
+            .spacing(20),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+impl Settings {
     /// Processes messages and updates the component's state.
     pub fn update(&mut self, message: Message) {
         match message {
@@ -114,25 +539,319 @@ impl Settings {
                 SessionType::Pomodoro => self.work_theme = theme,
                 SessionType::Break => self.break_theme = theme,
             },
-            Message::Submit => {
-                let _ = persistence::save("settings.json", &self);
+            Message::AutoStartToggled(session, value) => match session {
+                SessionType::Pomodoro => self.auto_start_pomodoros = value,
+                SessionType::Break => self.auto_start_breaks = value,
+            },
+            Message::GraceChanged(value) => self.auto_start_grace_sec = value,
+            Message::FlowtimeToggled(value) => self.flowtime = value,
+            Message::FlowRatioChanged(value) => self.flow_break_ratio = value,
+            Message::FlowBreakModeChanged(mode) => self.flow_break_mode = mode,
+            Message::FlowTableWorkChanged(index, value) => {
+                if let Some(row) = self.flow_break_table.get_mut(index) {
+                    row.0 = value;
+                }
+            }
+            Message::FlowTableBreakChanged(index, value) => {
+                if let Some(row) = self.flow_break_table.get_mut(index) {
+                    row.1 = value;
+                }
+            }
+            Message::AddFlowTableRow => {
+                let (work, rest) = self.flow_break_table.last().copied().unwrap_or((25, 5));
+                self.flow_break_table
+                    .push((work.saturating_add(30), rest.saturating_add(5)));
+            }
+            Message::DeleteFlowTableRow(index) => {
+                if index < self.flow_break_table.len() {
+                    self.flow_break_table.remove(index);
+                }
+            }
+            Message::OvertimeCapChanged(value) => self.overtime.cap_min = value,
+            Message::OvertimeAutoFinishChanged(value) => self.overtime.auto_finish_min = value,
+            Message::OvertimeFocusToggled(value) => self.overtime.counts_as_focus = value,
+            Message::LanguageChanged(language) => self.language = language,
+            Message::NotifyToggled(trigger, value) => {
+                self.notifications.get_mut(trigger).enabled = value;
+            }
+            Message::SoundChanged(trigger, sound) => {
+                self.notifications.get_mut(trigger).sound = sound;
             }
+            Message::UrgencyChanged(trigger, urgency) => {
+                self.notifications.get_mut(trigger).urgency = urgency;
+            }
+            Message::NotifyTimeoutChanged(trigger, value) => {
+                self.notifications.get_mut(trigger).timeout_sec = value;
+            }
+            Message::OvertimeReminderChanged(value) => {
+                self.notifications.overtime_after_min = value;
+            }
+            Message::OvertimeRepeatToggled(value) => self.notifications.overtime_repeat = value,
+            Message::GoalPomodorosChanged(value) => self.goal.pomodoros = value,
+            Message::GoalFocusChanged(value) => self.goal.focus_min = value,
+            Message::StreakGoalOnlyToggled(value) => self.goal.streak_goal_only = value,
+            Message::AutoCompleteToggled(value) => self.tasks.auto_complete = value,
+            Message::MaxTasksChanged(value) => self.tasks.max_tasks = value,
+            Message::AddWarning
+            | Message::DeleteWarning(_)
+            | Message::WarningSessionChanged(..)
+            | Message::WarningSecondsChanged(..)
+            | Message::HighlightChanged(_) => self.update_warning(&message),
+            Message::TemplateSelected(_)
+            | Message::AddTemplate
+            | Message::DeleteTemplate
+            | Message::TemplateNameChanged(_)
+            | Message::AddStep
+            | Message::DeleteStep(_)
+            | Message::StepNameChanged(..)
+            | Message::StepSessionChanged(..)
+            | Message::StepMinutesChanged(..)
+            | Message::StepThemeChanged(..) => self.update_template(message),
+            // Profile messages and saving are handled by `Profiles`.
+            Message::ProfileSelected(_)
+            | Message::NextProfile
+            | Message::AddProfile
+            | Message::DeleteProfile
+            | Message::ProfileNameChanged(_)
+            | Message::Preview(_)
+            | Message::Reload
+            | Message::Submit => {}
         }
     }
 
-    /// Builds the main view for the Settings tab.
-    pub fn view(&self) -> Element<'_, Message> {
-        scrollable(
-            column![
-                self.view_timer_settings(),
-                self.view_theme_settings(),
-                Self::view_shortcuts(),
-                button("Apply Settings").on_press(Message::Submit),
-            ]
-            .spacing(20)
-            .padding(10),
-        )
-        .into()
+    /// Processes messages that build or pick a session sequence template.
+    fn update_template(&mut self, message: Message) {
+        match message {
+            Message::TemplateSelected(index) => self.active_template = index,
+            Message::AddTemplate => {
+                self.templates.push(Template {
+                    name: format!("Sequence {}", self.templates.len() + 1),
+                    steps: vec![
+                        self.new_step("Focus", Session::Pomodoro, self.work_min),
+                        self.new_step("Break", Session::Break, self.break_min),
+                    ],
+                });
+                self.active_template = Some(self.templates.len() - 1);
+            }
+            Message::DeleteTemplate => {
+                if let Some(index) = self.active_template.take()
+                    && index < self.templates.len()
+                {
+                    self.templates.remove(index);
+                }
+            }
+            Message::TemplateNameChanged(name) => {
+                if let Some(template) = self.template_mut() {
+                    template.name = name;
+                }
+            }
+            Message::AddStep => {
+                let step = self.new_step("Focus", Session::Pomodoro, self.work_min);
+                if let Some(template) = self.template_mut() {
+                    template.steps.push(step);
+                }
+            }
+            Message::DeleteStep(index) => {
+                if let Some(template) = self.template_mut()
+                    && index < template.steps.len()
+                {
+                    template.steps.remove(index);
+                }
+            }
+            Message::StepNameChanged(index, name) => {
+                if let Some(step) = self.step_mut(index) {
+                    step.name = name;
+                }
+            }
+            Message::StepSessionChanged(index, session) => {
+                if let Some(step) = self.step_mut(index) {
+                    step.session = session;
+                }
+            }
+            Message::StepMinutesChanged(index, minutes) => {
+                if let Some(step) = self.step_mut(index) {
+                    step.minutes = minutes;
+                }
+            }
+            Message::StepThemeChanged(index, theme) => {
+                if let Some(step) = self.step_mut(index) {
+                    step.theme = theme;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Processes messages that configure the heads-ups before sessions run out.
+    fn update_warning(&mut self, message: &Message) {
+        let warnings = &mut self.notifications.warnings;
+        match *message {
+            Message::AddWarning => warnings.push(WarningSetting {
+                session: Session::Pomodoro,
+                seconds: 120,
+            }),
+            Message::DeleteWarning(index) if index < warnings.len() => {
+                warnings.remove(index);
+            }
+            Message::WarningSessionChanged(index, session) => {
+                if let Some(warning) = warnings.get_mut(index) {
+                    warning.session = session;
+                }
+            }
+            Message::WarningSecondsChanged(index, seconds) => {
+                if let Some(warning) = warnings.get_mut(index) {
+                    warning.seconds = seconds;
+                }
+            }
+            Message::HighlightChanged(value) => self.notifications.highlight_sec = value,
+            _ => {}
+        }
+    }
+
+    /// Returns the overtime policy used by the timer.
+    pub fn overtime_policy(&self) -> OvertimePolicy {
+        let limit = |value: u8| (value > 0).then(|| minutes(value));
+        OvertimePolicy {
+            cap: limit(self.overtime.cap_min),
+            auto_finish: limit(self.overtime.auto_finish_min),
+            counts_as_focus: self.overtime.counts_as_focus,
+        }
+    }
+
+    /// Returns the configuration of the Pomodoro tab.
+    pub fn pomodoro_config(&self) -> pomodoro::Config {
+        pomodoro::Config {
+            cycle: self.cycle(),
+            auto_start: self.auto_start(),
+            break_rule: self.break_rule(),
+            overtime_policy: self.overtime_policy(),
+            language: self.language.clone(),
+            alerts: self.notifications.clone(),
+            goal: self.goal(),
+            auto_complete: self.tasks.auto_complete,
+            max_tasks: (self.tasks.max_tasks > 0).then_some(usize::from(self.tasks.max_tasks)),
+        }
+    }
+
+    /// Returns the daily target of pomodoros and focused time.
+    pub fn goal(&self) -> Goal {
+        Goal {
+            pomodoros: u32::from(self.goal.pomodoros),
+            focused: Duration::from_secs(u64::from(self.goal.focus_min) * 60),
+        }
+    }
+
+    /// Returns the auto-start configuration used by the timer.
+    pub fn auto_start(&self) -> AutoStart {
+        AutoStart {
+            breaks: self.auto_start_breaks,
+            pomodoros: self.auto_start_pomodoros,
+            grace: Duration::from_secs(u64::from(self.auto_start_grace_sec)),
+        }
+    }
+
+    /// Returns the rule for the break earned by a counting up work session.
+    pub fn break_rule(&self) -> BreakRule {
+        if self.flow_break_mode == FlowBreakMode::Table {
+            let mut rows: Vec<(Duration, Duration)> = self
+                .flow_break_table
+                .iter()
+                .map(|&(work, rest)| (minutes(work), minutes(rest)))
+                .collect();
+            rows.sort_by_key(|(work, _)| *work);
+            BreakRule::Table(rows)
+        } else {
+            BreakRule::Ratio(u32::from(self.flow_break_ratio))
+        }
+    }
+
+    /// Returns the steps of the session cycle in use, together with the theme of each step.
+    ///
+    /// In Flowtime mode the cycle is a counting up work step followed by the earned break.
+    /// Otherwise it falls back to the classic pomodoro cycle when no template (or an empty one)
+    /// is selected.
+    pub fn cycle(&self) -> Vec<(Step, Theme)> {
+        if self.flowtime {
+            let flow = Step {
+                name: "Flow".to_string(),
+                session: Session::Pomodoro,
+                duration: Duration::ZERO,
+            };
+            let rest = Step {
+                name: Session::Break.to_string(),
+                session: Session::Break,
+                duration: minutes(self.break_min),
+            };
+            return vec![
+                (flow, self.work_theme.to_iced_theme()),
+                (rest, self.break_theme.to_iced_theme()),
+            ];
+        }
+
+        if let Some(template) = self.active_template.and_then(|i| self.templates.get(i))
+            && !template.steps.is_empty()
+        {
+            return template
+                .steps
+                .iter()
+                .map(|step| {
+                    let cycle_step = Step {
+                        name: step.name.clone(),
+                        session: step.session,
+                        duration: minutes(step.minutes),
+                    };
+                    (cycle_step, step.theme.to_iced_theme())
+                })
+                .collect();
+        }
+
+        // Classic cycle: work sessions separated by breaks, ending with a long break.
+        let work = (Session::Pomodoro, self.work_min, self.work_theme);
+        let short_break = (Session::Break, self.break_min, self.break_theme);
+        let long_break = (Session::LongBreak, self.long_break_min, self.break_theme);
+        (1..=self.long_break_after.max(1))
+            .flat_map(|count| {
+                let rest = if count < self.long_break_after {
+                    short_break
+                } else {
+                    long_break
+                };
+                [work, rest]
+            })
+            .map(|(session, length, theme)| {
+                let step = Step {
+                    name: session.to_string(),
+                    session,
+                    duration: minutes(length),
+                };
+                (step, theme.to_iced_theme())
+            })
+            .collect()
+    }
+
+    /// Creates a template step using the theme configured for its session type.
+    fn new_step(&self, name: &str, session: Session, minutes: u8) -> TemplateStep {
+        let theme = match session {
+            Session::Pomodoro => self.work_theme,
+            Session::Break | Session::LongBreak => self.break_theme,
+        };
+        TemplateStep {
+            name: name.to_string(),
+            session,
+            minutes,
+            theme,
+        }
+    }
+
+    /// Returns the selected template for editing, if any.
+    fn template_mut(&mut self) -> Option<&mut Template> {
+        self.active_template.and_then(|i| self.templates.get_mut(i))
+    }
+
+    /// Returns a step of the selected template for editing, if any.
+    fn step_mut(&mut self, index: usize) -> Option<&mut TemplateStep> {
+        self.template_mut()
+            .and_then(|template| template.steps.get_mut(index))
     }
 
     /// View section for configuring session timer durations.
@@ -166,6 +885,319 @@ impl Settings {
         .into()
     }
 
+    /// View section for building session sequence templates and switching between them.
+    fn view_sequence_settings(&self) -> Element<'_, Message> {
+        let selected = Some(self.active_template);
+        let template_radios = column(
+            std::iter::once(radio("Classic", None, selected, Message::TemplateSelected).into())
+                .chain(self.templates.iter().enumerate().map(|(i, template)| {
+                    radio(
+                        template.name.as_str(),
+                        Some(i),
+                        selected,
+                        Message::TemplateSelected,
+                    )
+                    .into()
+                })),
+        )
+        .spacing(5);
+
+        let mut content = column![
+            text("Sequences").size(20),
+            horizontal_rule(1),
+            template_radios,
+            button("New sequence").on_press(Message::AddTemplate),
+        ]
+        .spacing(10);
+
+        if let Some(template) = self.active_template.and_then(|i| self.templates.get(i)) {
+            let themes = ALL_THEMES.map(|(_, theme)| theme);
+            let steps = template.steps.iter().enumerate().map(|(i, step)| {
+                column![
+                    row![
+                        text_input("Step name", &step.name)
+                            .on_input(move |name| Message::StepNameChanged(i, name)),
+                        button(text("×").shaping(text::Shaping::Advanced))
+                            .style(button::danger)
+                            .on_press(Message::DeleteStep(i)),
+                    ]
+                    .spacing(10),
+                    row![
+                        pick_list(Session::ALL, Some(step.session), move |session| {
+                            Message::StepSessionChanged(i, session)
+                        }),
+                        number_input(&step.minutes, 1..=240, move |minutes| {
+                            Message::StepMinutesChanged(i, minutes)
+                        }),
+                        pick_list(themes, Some(step.theme), move |theme| {
+                            Message::StepThemeChanged(i, theme)
+                        }),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(5)
+                .into()
+            });
+
+            content = content.push(
+                column![
+                    text_input("Sequence name", &template.name)
+                        .on_input(Message::TemplateNameChanged),
+                    column(steps).spacing(15),
+                    row![
+                        button("Add step").on_press(Message::AddStep),
+                        button("Delete sequence")
+                            .on_press(Message::DeleteTemplate)
+                            .style(button::danger),
+                    ]
+                    .spacing(20),
+                ]
+                .spacing(10),
+            );
+        }
+
+        content.into()
+    }
+
+    /// View section for configuring the Flowtime mode and the breaks it earns.
+    fn view_flowtime_settings(&self) -> Element<'_, Message> {
+        let mut content = column![
+            text("Flowtime").size(20),
+            horizontal_rule(1),
+            checkbox("Count up work sessions", self.flowtime).on_toggle(Message::FlowtimeToggled),
+            row![
+                radio(
+                    "Break ratio",
+                    FlowBreakMode::Ratio,
+                    Some(self.flow_break_mode),
+                    Message::FlowBreakModeChanged
+                ),
+                radio(
+                    "Break table",
+                    FlowBreakMode::Table,
+                    Some(self.flow_break_mode),
+                    Message::FlowBreakModeChanged
+                ),
+            ]
+            .spacing(20),
+        ]
+        .spacing(10);
+
+        if self.flow_break_mode == FlowBreakMode::Table {
+            let rows = self
+                .flow_break_table
+                .iter()
+                .enumerate()
+                .map(|(i, &(work, rest))| {
+                    row![
+                        text("Work up to"),
+                        number_input(&work, 1..=240, move |value| {
+                            Message::FlowTableWorkChanged(i, value)
+                        }),
+                        text("break"),
+                        number_input(&rest, 1..=60, move |value| {
+                            Message::FlowTableBreakChanged(i, value)
+                        }),
+                        button(text("×").shaping(text::Shaping::Advanced))
+                            .style(button::danger)
+                            .on_press(Message::DeleteFlowTableRow(i)),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+                });
+            content = content
+                .push(column(rows).spacing(5))
+                .push(button("Add row").on_press(Message::AddFlowTableRow));
+        } else {
+            content = content
+                .push(text("Break is the work time divided by"))
+                .push(number_input(
+                    &self.flow_break_ratio,
+                    1..=20,
+                    Message::FlowRatioChanged,
+                ));
+        }
+
+        content.into()
+    }
+
+    /// View section for configuring how overtime is limited and logged.
+    fn view_overtime_settings(&self) -> Element<'_, Message> {
+        column![
+            text("Overtime").size(20),
+            horizontal_rule(1),
+            row![
+                column![
+                    text("Max overtime (0 = none)"),
+                    number_input(&self.overtime.cap_min, 0..=240, Message::OvertimeCapChanged)
+                ],
+                column![
+                    text("Finish after (0 = never)"),
+                    number_input(
+                        &self.overtime.auto_finish_min,
+                        0..=240,
+                        Message::OvertimeAutoFinishChanged
+                    )
+                ],
+            ]
+            .spacing(20),
+            checkbox(
+                "Count overtime as focus time",
+                self.overtime.counts_as_focus
+            )
+            .on_toggle(Message::OvertimeFocusToggled),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for configuring how the notification of each event is shown.
+    fn view_notification_settings(&self) -> Element<'_, Message> {
+        let events = Trigger::ALL.map(|trigger| {
+            let behavior = self.notifications.get(trigger);
+            column![
+                checkbox(trigger.to_string(), behavior.enabled)
+                    .on_toggle(move |value| Message::NotifyToggled(trigger, value)),
+                text_input("Sound name or file path", &behavior.sound)
+                    .on_input(move |sound| Message::SoundChanged(trigger, sound)),
+                row![
+                    text("Urgency"),
+                    pick_list(Urgency::ALL, Some(behavior.urgency), move |urgency| {
+                        Message::UrgencyChanged(trigger, urgency)
+                    }),
+                    text("Timeout (s, 0 = default)"),
+                    number_input(&behavior.timeout_sec, 0..=120, move |value| {
+                        Message::NotifyTimeoutChanged(trigger, value)
+                    }),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        column![
+            text("Notifications").size(20),
+            horizontal_rule(1),
+            column(events).spacing(15),
+            text("Remind after overtime (minutes, 0 = never)"),
+            number_input(
+                &self.notifications.overtime_after_min,
+                0..=120,
+                Message::OvertimeReminderChanged
+            ),
+            checkbox(
+                "Repeat the reminder while in overtime",
+                self.notifications.overtime_repeat
+            )
+            .on_toggle(Message::OvertimeRepeatToggled),
+            self.view_warning_settings(),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for the heads-ups before sessions run out and the timer's colour change.
+    fn view_warning_settings(&self) -> Element<'_, Message> {
+        let warnings = self
+            .notifications
+            .warnings
+            .iter()
+            .enumerate()
+            .map(|(i, warning)| {
+                row![
+                    text("Warn"),
+                    number_input(&warning.seconds, 1..=3600, move |value| {
+                        Message::WarningSecondsChanged(i, value)
+                    }),
+                    text("s before"),
+                    pick_list(Session::ALL, Some(warning.session), move |session| {
+                        Message::WarningSessionChanged(i, session)
+                    }),
+                    button(text("×").shaping(text::Shaping::Advanced))
+                        .style(button::danger)
+                        .on_press(Message::DeleteWarning(i)),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            });
+
+        column![
+            column(warnings).spacing(5),
+            button("Add warning").on_press(Message::AddWarning),
+            text("Change timer colour in the last seconds (0 = never)"),
+            number_input(
+                &self.notifications.highlight_sec,
+                0..=3600,
+                Message::HighlightChanged
+            ),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for configuring the daily goal and how it affects streaks.
+    fn view_goal_settings(&self) -> Element<'_, Message> {
+        column![
+            text("Daily Goal").size(20),
+            horizontal_rule(1),
+            row![
+                column![
+                    text("Pomodoros (0 = none)"),
+                    number_input(&self.goal.pomodoros, 0..=50, Message::GoalPomodorosChanged)
+                ],
+                column![
+                    text("Focus minutes (0 = none)"),
+                    number_input(&self.goal.focus_min, 0..=1440, Message::GoalFocusChanged)
+                ],
+            ]
+            .spacing(20),
+            checkbox(
+                "Count only days that met the goal in streaks",
+                self.goal.streak_goal_only
+            )
+            .on_toggle(Message::StreakGoalOnlyToggled),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for configuring the task list.
+    fn view_task_settings(&self) -> Element<'_, Message> {
+        column![
+            text("Tasks").size(20),
+            horizontal_rule(1),
+            checkbox(
+                "Complete a task when all of its subtasks are done",
+                self.tasks.auto_complete
+            )
+            .on_toggle(Message::AutoCompleteToggled),
+            text("Maximum tasks (0 = no limit)"),
+            number_input(&self.tasks.max_tasks, 0..=999, Message::MaxTasksChanged),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View section for configuring which sessions start automatically.
+    fn view_auto_start_settings(&self) -> Element<'_, Message> {
+        column![
+            text("Auto Start").size(20),
+            horizontal_rule(1),
+            checkbox("Start breaks automatically", self.auto_start_breaks)
+                .on_toggle(|value| Message::AutoStartToggled(SessionType::Break, value)),
+            checkbox("Start pomodoros automatically", self.auto_start_pomodoros)
+                .on_toggle(|value| Message::AutoStartToggled(SessionType::Pomodoro, value)),
+            text("Grace period (seconds)"),
+            number_input(&self.auto_start_grace_sec, 0..=60, Message::GraceChanged),
+        ]
+        .spacing(10)
+        .into()
+    }
+
     /// View section for configuring color themes for both sessions.
     fn view_theme_settings(&self) -> Element<'_, Message> {
         // Helper function to generate a column of theme radio buttons.
@@ -229,15 +1261,30 @@ impl Settings {
             shortcut_row("Space", "Start/Stop timer"),
             shortcut_row("r", "Reset timer"),
             shortcut_row("f", "Finish session"),
+            shortcut_row("i", "Log internal interruption"),
+            shortcut_row("o", "Log external interruption"),
             shortcut_row("n", "Focus new task input"),
             shortcut_row("a", "Activate/Deactivate first task"),
             shortcut_row("↑ / ↓", "Navigate active task"),
+            shortcut_row("Shift + ↑ / ↓", "Move active task up/down"),
+            shortcut_row("p", "Cycle priority of active task"),
+            shortcut_row("h", "Hide/Show completed tasks"),
+            shortcut_row("/", "Focus task filter"),
             shortcut_row("e", "Edit active task"),
             shortcut_row("s", "Complete active task"),
             shortcut_row("d", "Delete active task"),
+            shortcut_row("+ / -", "Raise/Lower estimate of active task"),
+            shortcut_row("c", "Add subtask to active task"),
+            shortcut_row("[ / ]", "Navigate subtasks of active task"),
+            shortcut_row("t", "Tick/Untick selected subtask"),
+            shortcut_row("Shift + e", "Edit selected subtask"),
+            shortcut_row("Shift + d", "Delete selected subtask"),
             shortcut_row("x", "End day"),
             shortcut_row("Ctrl + Tab", "Next tab"),
             shortcut_row("Shift + Tab", "Previous tab"),
+            shortcut_row("Ctrl + p", "Next settings profile"),
+            shortcut_row("Ctrl + z", "Undo task or report change"),
+            shortcut_row("Ctrl + Shift + z", "Redo undone change"),
         ]
         .spacing(10);
 
@@ -0,0 +1,1104 @@
+//! GUI-free timer engine that drives the Pomodoro session state machine.
+//!
+//! The engine reads the time from an injectable `Clock` and reports what happened as
+//! domain `Event`s, so the same logic can be driven by the GUI or any other front end.
+
+use iced::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+/// Source of the current time for the timer engine.
+pub trait Clock {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+
+    /// Returns the current wall-clock time, used to persist the timer across restarts.
+    fn wall_now(&self) -> DateTime<Local>;
+}
+
+/// Clock backed by the system's monotonic time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall_now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Represents the operational state of the timer.
+#[derive(Debug, Clone, Copy)]
+pub enum State {
+    Idle,
+    Waiting { starts: Instant },
+    Ticking { expires: Instant },
+    Overtime { last_tick: Instant },
+}
+
+/// Represents the type of session currently active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Session {
+    Pomodoro,
+    Break,
+    LongBreak,
+}
+
+impl Session {
+    /// List of all session types, used for picking the type of a step.
+    pub const ALL: [Session; 3] = [Session::Pomodoro, Session::Break, Session::LongBreak];
+}
+
+impl std::fmt::Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pomodoro => write!(f, "Pomodoro"),
+            Self::Break => write!(f, "Break"),
+            Self::LongBreak => write!(f, "Long Break"),
+        }
+    }
+}
+
+/// A single step of the session cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub name: String,
+    pub session: Session,
+    /// Length of the step, zero for a work step that counts up until it is finished.
+    pub duration: Duration,
+}
+
+impl Step {
+    /// Returns whether this is a work step without fixed length (Flowtime).
+    pub fn counts_up(&self) -> bool {
+        self.session == Session::Pomodoro && self.duration.is_zero()
+    }
+}
+
+/// Determines the break earned by a counting up work session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakRule {
+    /// The break is the work time divided by the given number.
+    Ratio(u32),
+    /// Pairs of `(work up to, break)`; work longer than every entry earns the last break.
+    Table(Vec<(Duration, Duration)>),
+}
+
+impl BreakRule {
+    /// Returns the break earned by the given work time.
+    pub fn break_for(&self, work: Duration) -> Duration {
+        match self {
+            Self::Ratio(divisor) => work / (*divisor).max(1),
+            Self::Table(rows) => rows
+                .iter()
+                .find(|(up_to, _)| work <= *up_to)
+                .or(rows.last())
+                .map_or(Duration::ZERO, |(_, rest)| *rest),
+        }
+    }
+}
+
+/// Controls which sessions start automatically after the previous one expires.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoStart {
+    /// Start the break right after a work session expires.
+    pub breaks: bool,
+    /// Start the next work session right after a break expires.
+    pub pomodoros: bool,
+    /// Time to wait before the next session starts.
+    pub grace: Duration,
+}
+
+/// Limits how overtime of a session is logged.
+#[derive(Debug, Clone, Copy)]
+pub struct OvertimePolicy {
+    /// Overtime beyond this limit is not logged.
+    pub cap: Option<Duration>,
+    /// The session is finished automatically after this much overtime.
+    pub auto_finish: Option<Duration>,
+    /// Whether logged overtime adds to the focused time.
+    pub counts_as_focus: bool,
+}
+
+impl Default for OvertimePolicy {
+    fn default() -> Self {
+        Self {
+            cap: None,
+            auto_finish: None,
+            counts_as_focus: true,
+        }
+    }
+}
+
+/// A heads-up sent when a session is about to run out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Warning {
+    /// The type of sessions the warning is sent for.
+    pub session: Session,
+    /// Time left in the session when the warning is sent.
+    pub before: Duration,
+}
+
+/// Time spent in a session segment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spent {
+    /// Time that counts as focused (or rest) time.
+    pub focus: Duration,
+    /// Logged time after the session reached zero.
+    pub overtime: Duration,
+    /// Part of the overtime included in `focus`.
+    pub counted: Duration,
+}
+
+/// Kind of an interruption, following the Pomodoro Technique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    /// Something the user thought of themselves (e.g. a sudden urge to check mail).
+    Internal,
+    /// Something coming from others (e.g. a phone call or a colleague).
+    External,
+}
+
+/// Counts of internal and external interruptions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interruptions {
+    pub internal: u32,
+    pub external: u32,
+}
+
+impl Interruptions {
+    /// Counts one more interruption of the given kind.
+    pub fn add(&mut self, kind: Interruption) {
+        match kind {
+            Interruption::Internal => self.internal = self.internal.saturating_add(1),
+            Interruption::External => self.external = self.external.saturating_add(1),
+        }
+    }
+
+    /// Returns the sum of both counts.
+    pub fn merged(self, other: Self) -> Self {
+        Self {
+            internal: self.internal.saturating_add(other.internal),
+            external: self.external.saturating_add(other.external),
+        }
+    }
+}
+
+/// Domain events reported by the engine when the timer changes its state.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The session started or resumed counting down.
+    Started,
+    /// The session crossed a warning threshold, with the given time left.
+    Warning(Duration),
+    /// The session reached zero and the timer switched to overtime.
+    Expired(Session),
+    /// The overtime of the session grew, see `TimerEngine::overtime`.
+    Overtime,
+    /// The overtime reached the limit after which the session should be finished.
+    OvertimeLimit,
+    /// The session was finished and the engine moved on to the next step.
+    Finished(Summary),
+}
+
+/// Timing details of a finished session.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub session: Session,
+    /// The name of the finished step.
+    pub step: String,
+    /// The time logged for the last segment of the session.
+    pub spent: Spent,
+    /// When the session was first started, `None` if it was never started.
+    pub started_at: Option<DateTime<Local>>,
+    pub ended_at: DateTime<Local>,
+    /// Total time the session spent paused after it was started.
+    pub paused: Duration,
+    /// How many times the session was paused after it was started.
+    pub pauses: u32,
+    pub interruptions: Interruptions,
+}
+
+/// Persistable state of the timer, anchored to wall-clock time.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(default)]
+    step: usize,
+    remaining: Duration,
+    last_done: Duration,
+    overtime: Duration,
+    /// The moment the running session expires (or expired), `None` if the timer is idle.
+    expires_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    started_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    paused: Duration,
+    #[serde(default)]
+    paused_since: Option<DateTime<Local>>,
+    #[serde(default)]
+    session_spent: Duration,
+    #[serde(default)]
+    earned_break: Option<Duration>,
+    #[serde(default)]
+    pauses: u32,
+    #[serde(default)]
+    interruptions: Interruptions,
+    #[serde(default)]
+    warned_at: Option<Duration>,
+    #[serde(default)]
+    extended_overtime: Duration,
+}
+
+/// Holds the session durations and the running state of the timer.
+pub struct TimerEngine<C: Clock = SystemClock> {
+    /// Source of the current time.
+    clock: C,
+    /// The steps of the session cycle, never empty.
+    steps: Vec<Step>,
+    /// A new cycle that replaces `steps` once the started session is finished or reset.
+    pending_steps: Option<Vec<Step>>,
+    /// Which sessions start automatically after the previous one expires.
+    auto_start: AutoStart,
+    /// How long the break after a counting up work session is.
+    break_rule: BreakRule,
+    /// How overtime is limited and logged.
+    overtime_policy: OvertimePolicy,
+    /// Heads-ups sent before sessions run out.
+    warnings: Vec<Warning>,
+    /// The time left at the last warning sent in the current session, so each fires only once.
+    warned_at: Option<Duration>,
+    /// The index of the current step in the cycle.
+    step: usize,
+    /// The time left in the current session.
+    remaining: Duration,
+    /// The duration of the last completed session segment.
+    last_done: Duration,
+    /// Time elapsed after the timer reaches zero.
+    overtime: Duration,
+    /// The timer's operational state (e.g., Idle, Ticking).
+    state: State,
+    /// When the current session was first started.
+    started_at: Option<DateTime<Local>>,
+    /// Time the current session spent paused, excluding the ongoing pause.
+    paused: Duration,
+    /// How many times the current session was paused.
+    pauses: u32,
+    /// Interruptions logged during the current session.
+    interruptions: Interruptions,
+    /// When the ongoing pause of a started session began.
+    paused_since: Option<DateTime<Local>>,
+    /// Time spent in the earlier segments of the current session.
+    session_spent: Duration,
+    /// Break length earned by the last counting up work session, used by the following break.
+    earned_break: Option<Duration>,
+    /// Logged overtime of the current session before it was extended.
+    extended_overtime: Duration,
+    /// Counts the sessions, so a notification can tell whether its session is still current.
+    session_number: u64,
+}
+
+impl<C: Clock> TimerEngine<C> {
+    /// Creates an idle engine at the first step of the cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `steps` is empty.
+    pub fn new(
+        clock: C,
+        steps: Vec<Step>,
+        auto_start: AutoStart,
+        break_rule: BreakRule,
+        overtime_policy: OvertimePolicy,
+    ) -> Self {
+        let duration = steps[0].duration;
+        Self {
+            clock,
+            steps,
+            pending_steps: None,
+            auto_start,
+            break_rule,
+            overtime_policy,
+            warnings: Vec::new(),
+            warned_at: None,
+            step: 0,
+            remaining: duration,
+            last_done: duration,
+            overtime: Duration::ZERO,
+            state: State::Idle,
+            started_at: None,
+            paused: Duration::ZERO,
+            pauses: 0,
+            interruptions: Interruptions::default(),
+            paused_since: None,
+            session_spent: Duration::ZERO,
+            earned_break: None,
+            extended_overtime: Duration::ZERO,
+            session_number: 0,
+        }
+    }
+
+    /// Replaces the steps of the cycle. A running or paused session keeps its step and time,
+    /// and the new cycle takes over once it is finished or reset.
+    ///
+    /// Empty `steps` are ignored, since the cycle needs at least one step.
+    pub fn set_steps(&mut self, steps: Vec<Step>) {
+        if steps.is_empty() {
+            return;
+        }
+        self.pending_steps = (self.steps != steps).then_some(steps);
+        if self.started_at.is_none() {
+            // A session waiting for its auto-start still starts, with the new step's time.
+            let state = self.state;
+            self.reset();
+            if let State::Waiting { .. } = state {
+                self.state = state;
+            }
+        }
+    }
+
+    /// Returns whether a new cycle is waiting for the started session to finish.
+    pub fn has_pending_steps(&self) -> bool {
+        self.pending_steps.is_some()
+    }
+
+    /// Updates how long the break after a counting up work session is.
+    pub fn set_break_rule(&mut self, break_rule: BreakRule) {
+        self.break_rule = break_rule;
+    }
+
+    /// Updates how overtime is limited and logged.
+    pub fn set_overtime_policy(&mut self, overtime_policy: OvertimePolicy) {
+        self.overtime_policy = overtime_policy;
+    }
+
+    /// Updates the heads-ups sent before sessions run out.
+    pub fn set_warnings(&mut self, warnings: Vec<Warning>) {
+        self.warnings = warnings;
+    }
+
+    /// Returns the break earned so far, if the current step counts up.
+    pub fn earned_break(&self) -> Option<Duration> {
+        self.step()
+            .counts_up()
+            .then(|| self.break_rule.break_for(self.elapsed()))
+    }
+
+    /// Returns the time spent in the current session across all of its segments.
+    pub fn elapsed(&self) -> Duration {
+        self.session_spent.saturating_add(self.time_spent().focus)
+    }
+
+    /// Logs an interruption of the current session.
+    pub fn interrupt(&mut self, kind: Interruption) {
+        self.interruptions.add(kind);
+    }
+
+    /// Returns the interruptions logged during the current session.
+    pub fn interruptions(&self) -> Interruptions {
+        self.interruptions
+    }
+
+    /// Updates which sessions start automatically.
+    pub fn set_auto_start(&mut self, auto_start: AutoStart) {
+        self.auto_start = auto_start;
+    }
+
+    /// Returns whether the session following the expired `session` should start automatically.
+    pub fn auto_starts_after(&self, session: Session) -> bool {
+        match session {
+            Session::Pomodoro => self.auto_start.breaks,
+            Session::Break | Session::LongBreak => self.auto_start.pomodoros,
+        }
+    }
+
+    /// Returns the time left until a scheduled session starts, if one is waiting.
+    pub fn grace_left(&self) -> Option<Duration> {
+        match self.state {
+            State::Waiting { starts } => Some(starts.saturating_duration_since(self.clock.now())),
+            _ => None,
+        }
+    }
+
+    /// Returns the current step of the cycle.
+    pub fn step(&self) -> &Step {
+        &self.steps[self.step]
+    }
+
+    /// Returns the step that follows the current one.
+    pub fn next_step(&self) -> &Step {
+        &self.steps[(self.step + 1) % self.steps.len()]
+    }
+
+    /// Returns how many work steps of the cycle are done and how many it has in total.
+    pub fn cycle_progress(&self) -> (usize, usize) {
+        let is_work = |step: &&Step| step.session == Session::Pomodoro;
+        let done = self.steps[..self.step].iter().filter(is_work).count();
+        let total = self.steps.iter().filter(is_work).count();
+        (done, total)
+    }
+
+    /// Returns the number of the current session, which changes whenever it is finished or reset.
+    pub fn session_number(&self) -> u64 {
+        self.session_number
+    }
+
+    /// Returns the index of the current step in the cycle.
+    pub fn step_index(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the current session type.
+    pub fn session(&self) -> Session {
+        self.step().session
+    }
+
+    /// Returns the timer's operational state.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Returns the time left in the current session.
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Returns the time elapsed after the timer reached zero.
+    pub fn overtime(&self) -> Duration {
+        self.overtime
+    }
+
+    /// Returns the full duration of the current session, or the earned break if there is one.
+    pub fn session_duration(&self) -> Duration {
+        match self.earned_break {
+            Some(earned) if self.session() != Session::Pomodoro => earned,
+            _ => self.step().duration,
+        }
+    }
+
+    /// Counts down the timer. If timer is zero, then switches to counting up the overtime.
+    pub fn tick(&mut self) -> Option<Event> {
+        let now = self.clock.now();
+        let auto_finish = self
+            .overtime_policy
+            .auto_finish
+            .filter(|_| !self.step().counts_up());
+        match &mut self.state {
+            State::Ticking { expires } => {
+                if let Some(duration) = expires.checked_duration_since(now) {
+                    self.remaining = duration;
+                    self.crossed_warning().map(Event::Warning)
+                } else {
+                    self.remaining = Duration::ZERO;
+                    self.state = State::Overtime { last_tick: now };
+                    Some(Event::Expired(self.session()))
+                }
+            }
+            State::Overtime { last_tick } => {
+                self.overtime = self.overtime.saturating_add(now - *last_tick);
+                *last_tick = now;
+                match auto_finish {
+                    Some(limit) if self.overtime >= limit => Some(Event::OvertimeLimit),
+                    _ => Some(Event::Overtime),
+                }
+            }
+            State::Waiting { starts } if *starts <= now => Some(self.start()),
+            State::Waiting { .. } | State::Idle => None,
+        }
+    }
+
+    /// Starts or stops the timer. A session waiting for its auto-start starts immediately.
+    pub fn toggle(&mut self) -> Option<Event> {
+        if let State::Idle | State::Waiting { .. } = self.state {
+            Some(self.start())
+        } else {
+            self.pause();
+            None
+        }
+    }
+
+    /// Starts the current session after the grace period, or immediately without one.
+    pub fn schedule_start(&mut self) -> Option<Event> {
+        if self.auto_start.grace.is_zero() {
+            Some(self.start())
+        } else {
+            let starts = self.clock.now() + self.auto_start.grace;
+            self.state = State::Waiting { starts };
+            None
+        }
+    }
+
+    /// Stops the timer without changing the remaining time.
+    pub fn pause(&mut self) {
+        if let State::Ticking { .. } | State::Overtime { .. } = self.state {
+            self.paused_since = Some(self.clock.wall_now());
+            self.pauses = self.pauses.saturating_add(1);
+        }
+        self.state = State::Idle;
+    }
+
+    /// Gives the current session more time. An expired session counts down again, and
+    /// its overtime so far becomes regular session time as far as the overtime policy logs it.
+    pub fn extend(&mut self, extra: Duration) {
+        if self.step().counts_up() {
+            return;
+        }
+        if self.remaining.is_zero() {
+            let spent = self.time_spent();
+            self.last_done = spent.focus;
+            self.extended_overtime = spent.overtime;
+        }
+        self.last_done = self.last_done.saturating_add(extra);
+        self.remaining = self.remaining.saturating_add(extra);
+        self.overtime = Duration::ZERO;
+        self.warned_at = None;
+        self.state = match self.state {
+            State::Ticking { expires } => State::Ticking {
+                expires: expires + extra,
+            },
+            State::Overtime { .. } => State::Ticking {
+                expires: self.clock.now() + self.remaining,
+            },
+            state => state,
+        };
+    }
+
+    /// Finishes the current session and switches to the next step of the cycle.
+    pub fn finish(&mut self) -> Event {
+        let session = self.session();
+        let ended_at = self.clock.wall_now();
+        let ongoing_pause = self
+            .paused_since
+            .and_then(|since| (ended_at - since).to_std().ok())
+            .unwrap_or_default();
+        let summary = Summary {
+            session,
+            step: self.step().name.clone(),
+            spent: self.time_spent(),
+            started_at: self.started_at,
+            ended_at,
+            paused: self.paused.saturating_add(ongoing_pause),
+            pauses: self.pauses,
+            interruptions: self.interruptions,
+        };
+
+        let earned_break = self.earned_break();
+        self.step += 1;
+        self.apply_pending_steps();
+        self.step %= self.steps.len();
+        self.earned_break = earned_break.filter(|_| self.session() != Session::Pomodoro);
+        self.reset();
+
+        Event::Finished(summary)
+    }
+
+    /// Resets the timer to the current session's full duration.
+    pub fn reset(&mut self) {
+        if self.apply_pending_steps() {
+            self.step %= self.steps.len();
+            self.earned_break = None;
+        }
+        self.remaining = self.session_duration();
+        self.last_done = self.remaining;
+        self.overtime = Duration::ZERO;
+        self.state = State::Idle;
+        self.started_at = None;
+        self.paused = Duration::ZERO;
+        self.pauses = 0;
+        self.interruptions = Interruptions::default();
+        self.paused_since = None;
+        self.session_spent = Duration::ZERO;
+        self.warned_at = None;
+        self.extended_overtime = Duration::ZERO;
+        self.session_number = self.session_number.wrapping_add(1);
+    }
+
+    /// Stops the timer and returns the time spent in the current segment,
+    /// so that the next segment starts counting from the remaining time.
+    pub fn split(&mut self) -> Spent {
+        let spent = self.time_spent();
+        self.session_spent = self.session_spent.saturating_add(spent.focus);
+        self.last_done = self.remaining;
+        self.overtime = Duration::ZERO;
+        self.extended_overtime = Duration::ZERO;
+        self.pause();
+        spent
+    }
+
+    /// Captures the timer state with the running session anchored to the wall clock.
+    pub fn snapshot(&self) -> Snapshot {
+        let wall_now = self.clock.wall_now();
+        let expires_at = match self.state {
+            State::Idle | State::Waiting { .. } => None,
+            State::Ticking { expires } => {
+                let left = expires.saturating_duration_since(self.clock.now());
+                Some(wall_now + TimeDelta::from_std(left).unwrap_or_default())
+            }
+            State::Overtime { .. } => {
+                Some(wall_now - TimeDelta::from_std(self.overtime).unwrap_or_default())
+            }
+        };
+
+        Snapshot {
+            step: self.step,
+            remaining: self.remaining,
+            last_done: self.last_done,
+            overtime: self.overtime,
+            expires_at,
+            started_at: self.started_at,
+            paused: self.paused,
+            paused_since: self.paused_since,
+            session_spent: self.session_spent,
+            earned_break: self.earned_break,
+            pauses: self.pauses,
+            interruptions: self.interruptions,
+            warned_at: self.warned_at,
+            extended_overtime: self.extended_overtime,
+        }
+    }
+
+    /// Restores a saved timer state, resuming a running session with the correct remaining
+    /// time or going straight into overtime if it expired in the meantime.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.step = snapshot.step % self.steps.len();
+        self.remaining = snapshot.remaining;
+        self.last_done = snapshot.last_done;
+        self.overtime = snapshot.overtime;
+        self.state = State::Idle;
+        self.started_at = snapshot.started_at;
+        self.paused = snapshot.paused;
+        self.paused_since = snapshot.paused_since;
+        self.session_spent = snapshot.session_spent;
+        self.earned_break = snapshot.earned_break;
+        self.pauses = snapshot.pauses;
+        self.interruptions = snapshot.interruptions;
+        self.warned_at = snapshot.warned_at;
+        self.extended_overtime = snapshot.extended_overtime;
+
+        if let Some(expires_at) = snapshot.expires_at {
+            let now = self.clock.now();
+            let wall_now = self.clock.wall_now();
+            match (expires_at - wall_now).to_std() {
+                Ok(left) if !left.is_zero() => {
+                    self.remaining = left;
+                    self.state = State::Ticking {
+                        expires: now + left,
+                    };
+                }
+                _ => {
+                    self.remaining = Duration::ZERO;
+                    self.overtime = (wall_now - expires_at).to_std().unwrap_or_default();
+                    self.state = State::Overtime { last_tick: now };
+                }
+            }
+        }
+    }
+
+    /// Switches to the new cycle if there is one, returning whether it did. The caller keeps
+    /// the step index within the new cycle.
+    fn apply_pending_steps(&mut self) -> bool {
+        match self.pending_steps.take() {
+            Some(steps) => {
+                self.steps = steps;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Starts counting down the current session, accounting for the time it was paused.
+    fn start(&mut self) -> Event {
+        let wall_now = self.clock.wall_now();
+        self.started_at.get_or_insert(wall_now);
+        if let Some(since) = self.paused_since.take() {
+            let pause = (wall_now - since).to_std().unwrap_or_default();
+            self.paused = self.paused.saturating_add(pause);
+        }
+
+        // A counting up step goes straight to counting the time it takes.
+        let now = self.clock.now();
+        self.state = if self.step().counts_up() {
+            State::Overtime { last_tick: now }
+        } else {
+            State::Ticking {
+                expires: now + self.remaining,
+            }
+        };
+        Event::Started
+    }
+
+    /// Returns the smallest warning threshold crossed since the last warning of the session.
+    ///
+    /// Thresholds that were all crossed at once (e.g. after a restart) send only one warning.
+    fn crossed_warning(&mut self) -> Option<Duration> {
+        let session = self.session();
+        let duration = self.session_duration();
+        let crossed = self
+            .warnings
+            .iter()
+            .filter(|warning| warning.session == session && warning.before < duration)
+            .map(|warning| warning.before)
+            .filter(|&before| {
+                self.remaining <= before && self.warned_at.is_none_or(|warned| before < warned)
+            })
+            .min()?;
+        self.warned_at = Some(crossed);
+        Some(crossed)
+    }
+
+    /// Returns the part of `overtime` that counts as focused time under the overtime policy.
+    fn counted(&self, overtime: Duration) -> Duration {
+        if self.overtime_policy.counts_as_focus {
+            overtime
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Calculates the total time spent in the current session segment, applying the
+    /// overtime policy. The time of a counting up step is never overtime.
+    fn time_spent(&self) -> Spent {
+        if self.step().counts_up() {
+            // A fixed session restored onto a counting up step still has time left.
+            Spent {
+                focus: self.last_done.saturating_sub(self.remaining) + self.overtime,
+                overtime: Duration::ZERO,
+                counted: Duration::ZERO,
+            }
+        } else if self.remaining.is_zero() {
+            // The cap covers the overtime logged before the session was extended too.
+            let policy = self.overtime_policy;
+            let overtime = policy.cap.map_or(self.overtime, |cap| {
+                self.overtime
+                    .min(cap.saturating_sub(self.extended_overtime))
+            });
+            let focus = if policy.counts_as_focus {
+                self.last_done + overtime
+            } else {
+                self.last_done
+            };
+            Spent {
+                focus,
+                overtime: self.extended_overtime + overtime,
+                counted: self.counted(self.extended_overtime + overtime),
+            }
+        } else {
+            Spent {
+                focus: self.last_done.saturating_sub(self.remaining),
+                overtime: self.extended_overtime,
+                counted: self.counted(self.extended_overtime),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Clock that only moves when the test advances it.
+    #[derive(Clone)]
+    struct ManualClock {
+        start: Instant,
+        wall_start: DateTime<Local>,
+        elapsed: Rc<Cell<Duration>>,
+    }
+
+    impl ManualClock {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                wall_start: Local::now(),
+                elapsed: Rc::default(),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            self.elapsed.set(self.elapsed.get() + duration);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed.get()
+        }
+
+        fn wall_now(&self) -> DateTime<Local> {
+            self.wall_start + TimeDelta::from_std(self.elapsed.get()).unwrap()
+        }
+    }
+
+    fn step(session: Session, minutes: u64) -> Step {
+        Step {
+            name: session.to_string(),
+            session,
+            duration: Duration::from_mins(minutes),
+        }
+    }
+
+    fn engine(clock: &ManualClock) -> TimerEngine<ManualClock> {
+        engine_with(clock, OvertimePolicy::default())
+    }
+
+    fn engine_with(clock: &ManualClock, policy: OvertimePolicy) -> TimerEngine<ManualClock> {
+        TimerEngine::new(
+            clock.clone(),
+            vec![step(Session::Pomodoro, 25), step(Session::Break, 5)],
+            AutoStart::default(),
+            BreakRule::Ratio(5),
+            policy,
+        )
+    }
+
+    /// Runs the first session into the given overtime, counted from the tick that expires it.
+    fn run_into_overtime(clock: &ManualClock, timer: &mut TimerEngine<ManualClock>, mins: u64) {
+        timer.toggle();
+        clock.advance(Duration::from_mins(26));
+        timer.tick();
+        clock.advance(Duration::from_mins(mins));
+        timer.tick();
+    }
+
+    fn finish(timer: &mut TimerEngine<ManualClock>) -> Summary {
+        match timer.finish() {
+            Event::Finished(summary) => summary,
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn tick_expires_then_counts_overtime() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        assert!(matches!(timer.toggle(), Some(Event::Started)));
+
+        clock.advance(Duration::from_mins(24));
+        assert!(timer.tick().is_none());
+        assert_eq!(timer.remaining(), Duration::from_mins(1));
+
+        clock.advance(Duration::from_mins(2));
+        assert!(matches!(
+            timer.tick(),
+            Some(Event::Expired(Session::Pomodoro))
+        ));
+        assert_eq!(timer.remaining(), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(30));
+        assert!(matches!(timer.tick(), Some(Event::Overtime)));
+        assert_eq!(timer.overtime(), Duration::from_secs(30));
+
+        let summary = finish(&mut timer);
+        assert_eq!(summary.spent.focus, Duration::from_secs(25 * 60 + 30));
+        assert_eq!(summary.spent.overtime, Duration::from_secs(30));
+        assert_eq!(summary.spent.counted, Duration::from_secs(30));
+        assert_eq!(timer.session(), Session::Break);
+    }
+
+    #[test]
+    fn extend_logs_overtime_within_the_cap() {
+        let clock = ManualClock::new();
+        let policy = OvertimePolicy {
+            cap: Some(Duration::from_mins(4)),
+            ..OvertimePolicy::default()
+        };
+        let mut timer = engine_with(&clock, policy);
+        run_into_overtime(&clock, &mut timer, 10);
+
+        timer.extend(Duration::from_mins(5));
+        assert!(matches!(timer.state(), State::Ticking { .. }));
+        assert_eq!(timer.remaining(), Duration::from_mins(5));
+        clock.advance(Duration::from_mins(7));
+        timer.tick();
+        clock.advance(Duration::from_mins(3));
+        timer.tick();
+
+        let summary = finish(&mut timer);
+        assert_eq!(summary.spent.focus, Duration::from_mins(25 + 4 + 5));
+        assert_eq!(summary.spent.overtime, Duration::from_mins(4));
+        assert_eq!(summary.spent.counted, Duration::from_mins(4));
+    }
+
+    #[test]
+    fn extend_keeps_overtime_out_of_focus_if_the_policy_says_so() {
+        let clock = ManualClock::new();
+        let policy = OvertimePolicy {
+            counts_as_focus: false,
+            ..OvertimePolicy::default()
+        };
+        let mut timer = engine_with(&clock, policy);
+        run_into_overtime(&clock, &mut timer, 3);
+
+        timer.extend(Duration::from_mins(5));
+        clock.advance(Duration::from_mins(2));
+        timer.tick();
+
+        let summary = finish(&mut timer);
+        assert_eq!(summary.spent.focus, Duration::from_mins(25 + 2));
+        assert_eq!(summary.spent.overtime, Duration::from_mins(3));
+        assert!(summary.spent.counted.is_zero());
+    }
+
+    #[test]
+    fn session_number_changes_when_the_session_ends() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        let first = timer.session_number();
+        timer.toggle();
+        timer.pause();
+        assert_eq!(timer.session_number(), first);
+        timer.reset();
+        let second = timer.session_number();
+        assert_ne!(second, first);
+        finish(&mut timer);
+        assert_ne!(timer.session_number(), second);
+    }
+
+    #[test]
+    fn pause_keeps_remaining_time_and_counts_the_pause() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        timer.toggle();
+        clock.advance(Duration::from_mins(10));
+        timer.tick();
+        assert!(timer.toggle().is_none());
+
+        clock.advance(Duration::from_mins(5));
+        assert!(timer.tick().is_none());
+        assert_eq!(timer.remaining(), Duration::from_mins(15));
+
+        assert!(matches!(timer.toggle(), Some(Event::Started)));
+        clock.advance(Duration::from_mins(5));
+        timer.tick();
+        assert_eq!(timer.remaining(), Duration::from_mins(10));
+
+        let summary = finish(&mut timer);
+        assert_eq!(summary.spent.focus, Duration::from_mins(15));
+        assert_eq!(summary.paused, Duration::from_mins(5));
+        assert_eq!(summary.pauses, 1);
+        assert_eq!(summary.started_at, Some(clock.wall_start));
+    }
+
+    #[test]
+    fn restore_resumes_a_running_session_after_restart() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        timer.toggle();
+        clock.advance(Duration::from_mins(10));
+        timer.tick();
+        let snapshot = timer.snapshot();
+
+        clock.advance(Duration::from_mins(5));
+        let mut restarted = engine(&clock);
+        restarted.restore(snapshot);
+        assert!(matches!(restarted.state(), State::Ticking { .. }));
+        assert_eq!(restarted.remaining(), Duration::from_mins(10));
+
+        let summary = finish(&mut restarted);
+        assert_eq!(summary.spent.focus, Duration::from_mins(15));
+    }
+
+    #[test]
+    fn restore_goes_into_overtime_if_the_session_expired_meanwhile() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        timer.toggle();
+        clock.advance(Duration::from_mins(20));
+        timer.tick();
+        let snapshot = timer.snapshot();
+
+        clock.advance(Duration::from_mins(8));
+        let mut restarted = engine(&clock);
+        restarted.restore(snapshot);
+        assert!(matches!(restarted.state(), State::Overtime { .. }));
+        assert_eq!(restarted.remaining(), Duration::ZERO);
+        assert_eq!(restarted.overtime(), Duration::from_mins(3));
+    }
+
+    #[test]
+    fn new_cycle_waits_for_the_started_session() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        finish(&mut timer);
+        timer.toggle();
+        clock.advance(Duration::from_mins(2));
+        timer.tick();
+
+        let template = vec![
+            step(Session::Pomodoro, 50),
+            step(Session::Pomodoro, 40),
+            step(Session::LongBreak, 20),
+        ];
+        timer.set_steps(template.clone());
+        assert_eq!(timer.session(), Session::Break);
+        assert_eq!(timer.remaining(), Duration::from_mins(3));
+
+        let summary = finish(&mut timer);
+        assert_eq!(summary.session, Session::Break);
+        assert_eq!(summary.spent.focus, Duration::from_mins(2));
+        assert_eq!(timer.step(), &template[2]);
+        assert_eq!(timer.remaining(), Duration::from_mins(20));
+    }
+
+    #[test]
+    fn switching_to_flowtime_credits_only_the_elapsed_time() {
+        let clock = ManualClock::new();
+        let flowtime = vec![step(Session::Pomodoro, 0), step(Session::Break, 5)];
+        let mut timer = engine(&clock);
+        timer.toggle();
+        clock.advance(Duration::from_mins(5));
+        timer.tick();
+
+        timer.set_steps(flowtime.clone());
+        clock.advance(Duration::from_mins(1));
+        timer.tick();
+        let snapshot = timer.snapshot();
+        assert_eq!(finish(&mut timer).spent.focus, Duration::from_mins(6));
+        assert_eq!(timer.step(), &flowtime[1]);
+
+        // After a restart the new cycle is in place right away.
+        let mut restarted = engine(&clock);
+        restarted.set_steps(flowtime);
+        restarted.restore(snapshot);
+        assert!(restarted.step().counts_up());
+        clock.advance(Duration::from_mins(1));
+        restarted.tick();
+        assert_eq!(finish(&mut restarted).spent.focus, Duration::from_mins(7));
+    }
+
+    #[test]
+    fn new_cycle_replaces_a_session_that_has_not_started() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        timer.set_steps(vec![step(Session::Pomodoro, 50), step(Session::Break, 10)]);
+        assert!(!timer.has_pending_steps());
+        assert_eq!(timer.remaining(), Duration::from_mins(50));
+    }
+
+    #[test]
+    fn restore_keeps_a_paused_session_paused() {
+        let clock = ManualClock::new();
+        let mut timer = engine(&clock);
+        timer.toggle();
+        clock.advance(Duration::from_mins(10));
+        timer.tick();
+        timer.pause();
+        let snapshot = timer.snapshot();
+
+        clock.advance(Duration::from_mins(30));
+        let mut restarted = engine(&clock);
+        restarted.restore(snapshot);
+        assert!(matches!(restarted.state(), State::Idle));
+        assert_eq!(restarted.remaining(), Duration::from_mins(15));
+
+        restarted.toggle();
+        let summary = finish(&mut restarted);
+        assert_eq!(summary.spent.focus, Duration::from_mins(10));
+        assert_eq!(summary.paused, Duration::from_mins(30));
+    }
+}